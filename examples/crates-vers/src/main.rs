@@ -1,12 +1,16 @@
-use std::ops::RangeBounds;
+use std::ops::{Bound, RangeBounds};
 use std::{cmp::min, collections::BTreeSet};
 
 use hibitset::{BitSet, BitSetLike};
 use indicatif::{ParallelProgressIterator, ProgressBar, ProgressFinish, ProgressStyle};
+use pubgrub::Range;
 use rayon::prelude::*;
 use semver::{Version, VersionReq};
-use semver_pubgrub::{SemverCompatibility, SemverPubgrub, SmallVersion, VersionLike};
+use semver_pubgrub::{testing, SemverCompatibility, SemverPubgrub, SmallVersion, VersionLike};
+use std::collections::HashMap;
+use std::hint::black_box;
 use std::io::Write;
+use std::time::{Duration, Instant};
 
 fn get_files_from_index() {
     println!("getting index");
@@ -74,6 +78,18 @@ fn read_files() -> Option<(Vec<Version>, Vec<(VersionReq, SemverPubgrub<Version>
     Some((versions, requirements))
 }
 
+/// Converts each of `reqs` with `convert`, returning how long it took.
+fn time_conversion(
+    reqs: &[VersionReq],
+    mut convert: impl FnMut(&VersionReq) -> SemverPubgrub<Version>,
+) -> Duration {
+    let start = Instant::now();
+    for req in reqs {
+        black_box(convert(black_box(req)));
+    }
+    start.elapsed()
+}
+
 fn main() {
     // TODO: Use real argument pursing
     let arg: Vec<_> = std::env::args().skip(1).collect();
@@ -82,13 +98,76 @@ fn main() {
         get_files_from_index();
     }
 
+    if arg.contains(&"bench-sample".to_string()) {
+        // The sample committed with semver-pubgrub, so this needs no data from the index.
+        let reqs: Vec<VersionReq> = testing::SAMPLE_REQUIREMENTS
+            .lines()
+            .map(|raw_req| raw_req.parse().unwrap())
+            .collect();
+        let elapsed: Duration = (0..1000)
+            .map(|_| time_conversion(&reqs, |req| req.into()))
+            .sum();
+        println!(
+            "converting the {} sample requirements 1000 times: {elapsed:?}",
+            reqs.len()
+        );
+        return;
+    }
+
     let intersection = arg.is_empty() || arg.contains(&"intersection".to_string());
     let contains = arg.is_empty() || arg.contains(&"contains".to_string());
+    let bench_intersection = arg.contains(&"bench-intersection".to_string());
+    let bench_conversion = arg.contains(&"bench-conversion".to_string());
 
     let Some((versions, requirements)) = read_files() else {
         panic!("no files");
     };
 
+    if bench_conversion {
+        let reqs: Vec<VersionReq> = requirements.iter().map(|(req, _)| req.clone()).collect();
+        // `from` used to start from `full()` and intersect every comparator into it,
+        // which is the same as one more intersection at the end.
+        let from_first = time_conversion(&reqs, |req| req.into());
+        let from_full =
+            time_conversion(&reqs, |req| SemverPubgrub::full().intersection(&req.into()));
+        println!(
+            "converting {} requirements: from the first comparator {from_first:?}, from full() {from_full:?}",
+            reqs.len()
+        );
+
+        // requirements.csv has each requirement once, but dependencies share the same few requirements.
+        let duplicated: Vec<VersionReq> = reqs
+            .iter()
+            .step_by(reqs.len() / 100 + 1)
+            .cycle()
+            .take(reqs.len())
+            .cloned()
+            .collect();
+        let uncached = time_conversion(&duplicated, |req| req.into());
+        let mut cache = HashMap::new();
+        let cached = time_conversion(&duplicated, |req| {
+            SemverPubgrub::from_req_cached(req, &mut cache)
+        });
+        println!(
+            "converting {} requirements with {} distinct: uncached {uncached:?}, from_req_cached {cached:?}",
+            duplicated.len(),
+            cache.len()
+        );
+
+        // `=` and `<=` with a pre-release end at `bump_pre`, which copies the whole pre-release.
+        for len in [8, 1 << 10, 1 << 16] {
+            let pre = "a".repeat(len);
+            let reqs: Vec<VersionReq> = (0..1000)
+                .map(|patch| VersionReq::parse(&format!("<=1.2.{patch}-{pre}")).unwrap())
+                .collect();
+            let elapsed = time_conversion(&reqs, |req| req.into());
+            println!(
+                "converting {} requirements with a {len} byte pre-release: {elapsed:?}",
+                reqs.len()
+            );
+        }
+    }
+
     assert!(versions.is_sorted());
     assert!(versions.is_sorted_by_key(|v| SemverCompatibility::from(v)));
 
@@ -187,6 +266,7 @@ fn main() {
                 for (pver2, bs2) in &requirements[(i + 1)..] {
                     let inter: SemverPubgrub<Version> = pver2.intersection(&pver);
                     assert_eq!(inter, pver.intersection(&pver2));
+                    assert_eq!(pver.is_disjoint(pver2), inter == SemverPubgrub::empty());
                     let bs_inter: BitSet = (bs & bs2).into_iter().collect();
                     if inter == SemverPubgrub::empty() {
                         assert!(bs_inter.is_empty());
@@ -207,8 +287,8 @@ fn main() {
                         }
                         let start = min_mat.unwrap_or(0).saturating_sub(30);
                         let end = min(max_mat.unwrap_or(!0).saturating_add(30), versions.len() - 1);
-                        for (id, inter_mat) in
-                            (start..=end).zip(inter.contains_many(versions[start..=end].iter()))
+                        for (id, inter_mat) in (start..=end)
+                            .zip(pver.intersect_over_universe(pver2, &versions[start..=end]))
                         {
                             let ver = &versions[id as usize];
                             let mat = bs_inter.contains(id as u32);
@@ -221,4 +301,88 @@ fn main() {
                 }
             })
     }
+
+    if bench_intersection {
+        // Membership over a fixed universe, by building the intersection or with `intersect_over_universe`.
+        let universe: Vec<Version> = versions.iter().step_by(100).cloned().collect();
+        let sample: Vec<&SemverPubgrub<Version>> = requirements
+            .iter()
+            .step_by(requirements.len() / 100 + 1)
+            .map(|(pver, _)| pver)
+            .collect();
+
+        let start = Instant::now();
+        let mut via_intersection = 0;
+        for pver in &sample {
+            for pver2 in &sample {
+                via_intersection += pver
+                    .intersection(pver2)
+                    .contains_many(universe.iter())
+                    .filter(|&mat| mat)
+                    .count();
+            }
+        }
+        let intersection_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut via_universe = 0;
+        for pver in &sample {
+            for pver2 in &sample {
+                via_universe += pver
+                    .intersect_over_universe(pver2, &universe)
+                    .into_iter()
+                    .filter(|&mat| mat)
+                    .count();
+            }
+        }
+        let universe_time = start.elapsed();
+
+        assert_eq!(via_intersection, via_universe);
+        println!(
+            "{} pairs over {} versions: intersection then contains_many {intersection_time:?}, intersect_over_universe {universe_time:?}",
+            sample.len() * sample.len(),
+            universe.len()
+        );
+
+        // `is_disjoint` used to check both parts without looking at the bounding ranges first.
+        let parts: Vec<(Range<Version>, Range<Version>)> = sample
+            .iter()
+            .map(|pver| {
+                let copy =
+                    |(from, to): (&Bound<Version>, &Bound<Version>)| (from.clone(), to.clone());
+                (
+                    Range::from_iter(pver.iter_normal().map(copy)),
+                    Range::from_iter(pver.iter_pre().map(copy)),
+                )
+            })
+            .collect();
+
+        let start = Instant::now();
+        let mut by_parts = 0;
+        for (normal, pre) in &parts {
+            for (normal2, pre2) in &parts {
+                if black_box(normal.is_disjoint(normal2) && pre.is_disjoint(pre2)) {
+                    by_parts += 1;
+                }
+            }
+        }
+        let parts_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut disjoint = 0;
+        for pver in &sample {
+            for pver2 in &sample {
+                if black_box(pver.is_disjoint(pver2)) {
+                    disjoint += 1;
+                }
+            }
+        }
+        let disjoint_time = start.elapsed();
+
+        assert_eq!(by_parts, disjoint);
+        println!(
+            "{} pairs, {disjoint} disjoint: is_disjoint of both parts {parts_time:?}, is_disjoint {disjoint_time:?}",
+            sample.len() * sample.len()
+        );
+    }
 }
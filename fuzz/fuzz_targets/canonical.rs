@@ -0,0 +1,57 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver::VersionReq;
+use semver_pubgrub::SemverPubgrub;
+use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
+
+// cargo fuzz run canonical
+
+fn canonical(req: &semver::VersionReq, req2: &semver::VersionReq, ver: &semver::Version) {
+    let pver: SemverPubgrub<semver::Version> = req.into();
+    let pver2: SemverPubgrub<semver::Version> = req2.into();
+    pver.check_invariants(req);
+    pver2.check_invariants(req2);
+
+    for set in [
+        pver.clone(),
+        pver.complement(),
+        pver.union(&pver2),
+        pver.intersection(&pver2.complement()),
+    ] {
+        let canonical = set.canonical();
+        assert_eq!(set.contains(ver), canonical.contains(ver));
+        assert_eq!(canonical, canonical.canonical());
+    }
+
+    // Splitting a set in two and putting it back together gives the same versions.
+    let rebuilt = pver
+        .intersection(&pver2)
+        .union(&pver.intersection(&pver2.complement()));
+    assert_eq!(pver.canonical(), rebuilt.canonical());
+
+    // The difference is already canonical, and contains the right versions.
+    let difference = pver.difference(&pver2);
+    assert_eq!(difference, difference.canonical());
+    assert_eq!(
+        difference.contains(ver),
+        pver.contains(ver) && !pver2.contains(ver)
+    );
+
+    if pver.canonical() == pver2.canonical() {
+        assert_eq!(pver.contains(ver), pver2.contains(ver));
+    }
+}
+
+fn case(req: Vec<ArbitraryComparator>, req2: Vec<ArbitraryComparator>, ver: ArbitraryVersion) {
+    let req: VersionReq = req.into_iter().map(|r| r.to_comparator()).collect();
+    let req2: VersionReq = req2.into_iter().map(|r| r.to_comparator()).collect();
+    let ver = ver.to_version();
+    canonical(&req, &req2, &ver);
+}
+
+fuzz_target!(|seed: (
+    Vec<ArbitraryComparator>,
+    Vec<ArbitraryComparator>,
+    ArbitraryVersion
+)| case(seed.0, seed.1, seed.2));
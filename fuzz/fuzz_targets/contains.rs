@@ -12,10 +12,25 @@ use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
 fn contains(req: &semver::VersionReq, ver: &semver::Version) {
     // println!("{req} |=> {ver}");
     let pver: SemverPubgrub<Version> = req.into();
+    pver.check_invariants(req);
     let neg = pver.complement();
     let mat = req.matches(&ver);
     assert_eq!(mat, pver.contains(&ver), "matches {} |=> {}", req, ver);
     assert_eq!(!mat, neg.contains(&ver), "!matches {} |=> {}", req, ver);
+    assert_eq!(
+        pver.not_contains(&ver),
+        neg.contains(&ver),
+        "not_contains {} |=> {}",
+        req,
+        ver
+    );
+    assert_eq!(
+        !mat,
+        pver.complement_canonical().contains(&ver),
+        "complement_canonical {} |=> {}",
+        req,
+        ver
+    );
 
     if mat {
         let bounding_range = pver.bounding_range();
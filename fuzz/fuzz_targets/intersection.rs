@@ -11,10 +11,13 @@ use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
 fn intersection(req: &semver::VersionReq, req2: &semver::VersionReq, ver: &semver::Version) {
     let pver: SemverPubgrub<semver::Version> = req.into();
     let pver2: SemverPubgrub<semver::Version> = req2.into();
+    pver.check_invariants(req);
+    pver2.check_invariants(req2);
 
     let inter: SemverPubgrub<semver::Version> = pver2.intersection(&pver);
     let mat = req.matches(&ver) && req2.matches(&ver);
     assert_eq!(mat, inter.contains(&ver));
+    assert_eq!(pver.is_disjoint(&pver2), inter == SemverPubgrub::empty());
     if mat {
         let bounding_range = pver.bounding_range();
         assert!(bounding_range.unwrap().contains(&ver));
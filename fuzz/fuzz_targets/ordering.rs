@@ -0,0 +1,34 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver::{Comparator, Version, VersionReq};
+use semver_pubgrub::SemverPubgrub;
+use semver_pubgrub_fuzz::ArbitraryComparator;
+
+// cargo fuzz run ordering
+
+fn ordering(cmps: &[Comparator], swaps: &[(u8, u8)]) {
+    let req = VersionReq {
+        comparators: cmps.to_vec(),
+    };
+    let mut permuted = cmps.to_vec();
+    if !permuted.is_empty() {
+        for &(a, b) in swaps {
+            let len = permuted.len();
+            permuted.swap(a as usize % len, b as usize % len);
+        }
+    }
+    let permuted = VersionReq {
+        comparators: permuted,
+    };
+    let pver: SemverPubgrub<Version> = (&req).into();
+    let pver_permuted: SemverPubgrub<Version> = (&permuted).into();
+    assert_eq!(pver, pver_permuted, "{} != {}", req, permuted);
+}
+
+fn case(req: Vec<ArbitraryComparator>, swaps: Vec<(u8, u8)>) {
+    let cmps: Vec<Comparator> = req.into_iter().map(|r| r.to_comparator()).collect();
+    ordering(&cmps, &swaps);
+}
+
+fuzz_target!(|seed: (Vec<ArbitraryComparator>, Vec<(u8, u8)>)| case(seed.0, seed.1));
@@ -0,0 +1,60 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver::{BuildMetadata, Version, VersionReq};
+use semver_pubgrub::SemverPubgrub;
+use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
+
+// cargo fuzz run roundtrip
+
+fn roundtrip(pver: &SemverPubgrub<Version>, vers: &[Version]) {
+    let Some(reqs) = pver.to_canonical_reqs() else {
+        return;
+    };
+    // The requirements have to survive being written out and read back in.
+    let reqs: Vec<VersionReq> = reqs
+        .iter()
+        .map(|req| VersionReq::parse(&req.to_string()).unwrap())
+        .collect();
+    let back = reqs
+        .iter()
+        .map(SemverPubgrub::from)
+        .fold(SemverPubgrub::empty(), |acc, r| acc.union(&r));
+    assert!(back.agrees_over(pver, vers.iter()), "{reqs:?}");
+    for ver in vers {
+        assert_eq!(
+            pver.contains(ver),
+            reqs.iter().any(|req| req.matches(ver)),
+            "{reqs:?} |=> {ver}"
+        );
+    }
+}
+
+fn case(reqs: Vec<(bool, Vec<ArbitraryComparator>)>, vers: Vec<ArbitraryVersion>) {
+    // Adding and taking away requirements makes sets that no single requirement matches.
+    let mut pver = SemverPubgrub::empty();
+    for (add, req) in reqs {
+        let req: VersionReq = req.into_iter().map(|r| r.to_comparator()).collect();
+        let other = SemverPubgrub::from(&req);
+        pver = if add {
+            pver.union(&other)
+        } else {
+            pver.difference(&other)
+        };
+    }
+    // `to_canonical_reqs` ignores build metadata, and `agrees_over` needs the versions in order.
+    let mut vers: Vec<Version> = vers
+        .into_iter()
+        .map(|v| Version {
+            build: BuildMetadata::EMPTY,
+            ..v.to_version()
+        })
+        .collect();
+    vers.sort();
+    vers.dedup();
+    roundtrip(&pver, &vers);
+}
+
+fuzz_target!(
+    |seed: (Vec<(bool, Vec<ArbitraryComparator>)>, Vec<ArbitraryVersion>)| case(seed.0, seed.1)
+);
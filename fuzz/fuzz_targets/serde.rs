@@ -0,0 +1,57 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver::{Version, VersionReq};
+use semver_pubgrub::{SemverPubgrub, SmallVersion, VersionLike};
+use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
+
+// cargo fuzz run serde --features serde
+
+fn roundtrip<V>(set: &SemverPubgrub<V>, ver: &V)
+where
+    V: VersionLike + std::fmt::Debug + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_string(set).unwrap();
+    let from_json: SemverPubgrub<V> = serde_json::from_str(&json).unwrap();
+    assert_eq!(set, &from_json, "json {}", json);
+    assert_eq!(set.contains(ver), from_json.contains(ver), "json {}", json);
+
+    let bytes = bincode::serialize(set).unwrap();
+    let from_bincode: SemverPubgrub<V> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(set, &from_bincode, "bincode {:?}", bytes);
+    assert_eq!(set.contains(ver), from_bincode.contains(ver));
+}
+
+fn serde(req: &semver::VersionReq, req2: &semver::VersionReq, ver: &semver::Version) {
+    let pver: SemverPubgrub<Version> = req.into();
+    let pver2: SemverPubgrub<Version> = req2.into();
+    // These cover sets where either field is empty and the other is not.
+    for set in [
+        pver.clone(),
+        pver.complement(),
+        pver.intersection(&pver2),
+        pver.union(&pver2.complement()),
+        SemverPubgrub::empty(),
+        SemverPubgrub::full(),
+    ] {
+        roundtrip(&set, ver);
+    }
+
+    let sver: SemverPubgrub<SmallVersion> = req.into();
+    let small: SmallVersion = ver.into();
+    roundtrip(&sver, &small);
+    roundtrip(&sver.complement(), &small);
+}
+
+fn case(req: Vec<ArbitraryComparator>, req2: Vec<ArbitraryComparator>, ver: ArbitraryVersion) {
+    let req: VersionReq = req.into_iter().map(|r| r.to_comparator()).collect();
+    let req2: VersionReq = req2.into_iter().map(|r| r.to_comparator()).collect();
+    let ver = ver.to_version();
+    serde(&req, &req2, &ver);
+}
+
+fuzz_target!(|seed: (
+    Vec<ArbitraryComparator>,
+    Vec<ArbitraryComparator>,
+    ArbitraryVersion
+)| case(seed.0, seed.1, seed.2));
@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver::{Version, VersionReq};
+use semver_pubgrub::SemverPubgrub;
+use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
+
+// cargo fuzz run simplify
+
+fn simplify(req: &VersionReq, vers: &[Version]) {
+    let pver: SemverPubgrub<Version> = req.into();
+    let simple = pver.simplify(vers.iter());
+    assert_eq!(simple, pver.simplify(vers.iter()), "deterministic {req}");
+    assert_eq!(
+        simple,
+        simple.simplify(vers.iter()),
+        "idempotent {req} over {vers:?}"
+    );
+    for ver in vers {
+        assert_eq!(pver.contains(ver), simple.contains(ver), "{req} |=> {ver}");
+    }
+}
+
+fn case(req: Vec<ArbitraryComparator>, vers: Vec<ArbitraryVersion>) {
+    let req: VersionReq = req.into_iter().map(|r| r.to_comparator()).collect();
+    // `simplify` needs the versions in order.
+    let mut vers: Vec<Version> = vers.into_iter().map(|v| v.to_version()).collect();
+    vers.sort();
+    vers.dedup();
+    simplify(&req, &vers);
+}
+
+fuzz_target!(|seed: (Vec<ArbitraryComparator>, Vec<ArbitraryVersion>)| case(seed.0, seed.1));
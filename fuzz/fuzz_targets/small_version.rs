@@ -0,0 +1,54 @@
+#![no_main]
+use std::ops::{Bound, RangeBounds};
+
+use libfuzzer_sys::fuzz_target;
+
+use semver::{Version, VersionReq};
+use semver_pubgrub::{SemverPubgrub, SmallVersion};
+use semver_pubgrub_fuzz::{ArbitraryComparator, ArbitraryVersion};
+
+// cargo fuzz run small_version
+
+fn to_version(bound: Bound<&SmallVersion>) -> Bound<Version> {
+    bound.map(|v| v.into_version())
+}
+
+fn small_version(req: &semver::VersionReq, ver: &semver::Version) {
+    let pver: SemverPubgrub<Version> = req.into();
+    let sver: SemverPubgrub<SmallVersion> = req.into();
+    let small: SmallVersion = ver.into();
+    assert_eq!(
+        pver.contains(ver),
+        sver.contains(&small),
+        "contains {} |=> {}",
+        req,
+        ver
+    );
+
+    let p_bounding_range = pver.bounding_range();
+    let s_bounding_range = sver.bounding_range();
+    assert_eq!(
+        p_bounding_range.map(|(s, e)| (s.cloned(), e.cloned())),
+        s_bounding_range.map(|(s, e)| (to_version(s), to_version(e))),
+        "bounding_range {}",
+        req
+    );
+    if sver.contains(&small) {
+        assert!(s_bounding_range.unwrap().contains(&small));
+    }
+
+    assert_eq!(
+        pver.only_one_compatibility_range(),
+        sver.only_one_compatibility_range(),
+        "only_one_compatibility_range {}",
+        req
+    );
+}
+
+fn case(req: Vec<ArbitraryComparator>, ver: ArbitraryVersion) {
+    let req: VersionReq = req.into_iter().map(|r| r.to_comparator()).collect();
+    let ver = ver.to_version();
+    small_version(&req, &ver);
+}
+
+fuzz_target!(|seed: (Vec<ArbitraryComparator>, ArbitraryVersion)| case(seed.0, seed.1));
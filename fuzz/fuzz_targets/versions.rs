@@ -19,6 +19,24 @@ fn versions(v1: &semver::Version, v2: &semver::Version) {
     let s2: SmallVersion = v2.into();
     assert_eq!(s1.cmp(&s2), v1.cmp(v2));
     assert_eq!(s1 == s2, v1 == v2);
+
+    // A packed `-0` pre-release against a version with the same `major.minor.patch`,
+    // so that the comparison gets all the way to the pre-release.
+    let zero = Version {
+        pre: semver::Prerelease::new("0").unwrap(),
+        build: semver::BuildMetadata::EMPTY,
+        ..v1.clone()
+    };
+    let same = Version {
+        major: v1.major,
+        minor: v1.minor,
+        patch: v1.patch,
+        ..v2.clone()
+    };
+    let s_zero: SmallVersion = (&zero).into();
+    let s_same: SmallVersion = (&same).into();
+    assert_eq!(s_zero.cmp(&s_same), zero.cmp(&same));
+    assert_eq!(s_same.cmp(&s_zero), same.cmp(&zero));
 }
 
 fn case(v1: ArbitraryVersion, v2: ArbitraryVersion) {
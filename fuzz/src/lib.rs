@@ -34,8 +34,9 @@ impl ArbitraryComparator {
             ArbitraryOp::Wildcard => semver::Op::Wildcard,
         };
         let patch = self.minor.and(self.patch);
-        let pre = patch
-            .and(self.pre)
+        // `semver` only parses a pre-release after a patch, but nothing stops one from being built without.
+        let pre = self
+            .pre
             .map(|p| p.to_string())
             .map(|p| semver::Prerelease::new(&p).unwrap())
             .unwrap_or_default();
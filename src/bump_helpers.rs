@@ -8,7 +8,22 @@ use semver::Version;
 
 use crate::VersionLike;
 
-pub(crate) fn bump_major<V: VersionLike>(v: &V) -> Bound<V> {
+/// The bound just past every version with the same major as `v`.
+///
+/// The next major version is returned at its smallest pre-release `-0`,
+/// as an `Excluded` upper bound this also leaves out the pre-releases of the next version.
+/// This is the same convention [SemverPubgrub](crate::SemverPubgrub) uses when converting a `VersionReq`.
+/// Returns `Unbounded` if there is no next major version.
+///
+/// ```
+/// # use std::ops::Bound;
+/// # use semver::Version;
+/// # use semver_pubgrub::bump_major;
+/// let v = Version::new(1, 2, 3);
+/// assert_eq!(bump_major(&v), Bound::Excluded(Version::parse("2.0.0-0").unwrap()));
+/// assert_eq!(bump_major(&Version::new(u64::MAX, 0, 0)), Bound::Unbounded);
+/// ```
+pub fn bump_major<V: VersionLike>(v: &V) -> Bound<V> {
     match v.major().checked_add(1) {
         Some(new) => Bound::Excluded(V::from(Version {
             major: new,
@@ -21,7 +36,21 @@ pub(crate) fn bump_major<V: VersionLike>(v: &V) -> Bound<V> {
     }
 }
 
-pub(crate) fn bump_minor<V: VersionLike>(v: &V) -> Bound<V> {
+/// The bound just past every version with the same major and minor as `v`.
+///
+/// Like [bump_major] the next version is at `-0`,
+/// and if the minor overflows this is [bump_major].
+///
+/// ```
+/// # use std::ops::Bound;
+/// # use semver::Version;
+/// # use semver_pubgrub::bump_minor;
+/// let v = Version::new(1, 2, 3);
+/// assert_eq!(bump_minor(&v), Bound::Excluded(Version::parse("1.3.0-0").unwrap()));
+/// let v = Version::new(1, u64::MAX, 3);
+/// assert_eq!(bump_minor(&v), Bound::Excluded(Version::parse("2.0.0-0").unwrap()));
+/// ```
+pub fn bump_minor<V: VersionLike>(v: &V) -> Bound<V> {
     match v.minor().checked_add(1) {
         Some(new) => Bound::Excluded(V::from(Version {
             major: v.major(),
@@ -34,7 +63,19 @@ pub(crate) fn bump_minor<V: VersionLike>(v: &V) -> Bound<V> {
     }
 }
 
-pub(crate) fn bump_patch<V: VersionLike>(v: &V) -> Bound<V> {
+/// The bound just past every version with the same major, minor and patch as `v`.
+///
+/// Like [bump_major] the next version is at `-0`,
+/// and if the patch overflows this is [bump_minor].
+///
+/// ```
+/// # use std::ops::Bound;
+/// # use semver::Version;
+/// # use semver_pubgrub::bump_patch;
+/// let v = Version::new(1, 2, 3);
+/// assert_eq!(bump_patch(&v), Bound::Excluded(Version::parse("1.2.4-0").unwrap()));
+/// ```
+pub fn bump_patch<V: VersionLike>(v: &V) -> Bound<V> {
     match v.patch().checked_add(1) {
         Some(new) => Bound::Excluded(V::from(Version {
             major: v.major(),
@@ -47,6 +88,30 @@ pub(crate) fn bump_patch<V: VersionLike>(v: &V) -> Bound<V> {
     }
 }
 
+/// The channel of a pre-release, like `rc` for `1.2.3-rc.1`.
+///
+/// This is the letters at the start of the first identifier of the pre-release.
+/// Returns None for a normal version, and for a pre-release that starts with a digit, like `1.2.3-0.alpha`.
+///
+/// ```
+/// # use semver::Version;
+/// # use semver_pubgrub::prerelease_channel;
+/// assert_eq!(prerelease_channel(&Version::parse("1.2.3-rc.1").unwrap()), Some("rc"));
+/// assert_eq!(prerelease_channel(&Version::new(1, 2, 3)), None);
+/// ```
+pub fn prerelease_channel<V: VersionLike>(v: &V) -> Option<&str> {
+    let first = v.pre().split('.').next()?;
+    let end = first
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(first.len());
+    (end > 0).then(|| &first[..end])
+}
+
+/// The smallest version after `v`, ignoring build metadata.
+///
+/// For a pre-release this appends `.0` to it.
+/// That copies and re-parses the pre-release, so the cost is linear in its length.
+/// There is no limit on the length beyond what `semver` accepts.
 pub(crate) fn bump_pre<V: VersionLike>(v: &V) -> Bound<V> {
     if !v.pre().is_empty() {
         Bound::Excluded(V::from(Version {
@@ -96,3 +161,108 @@ pub(crate) fn simplified_bounds_to_normal<V: VersionLike>(
     };
     (from, to)
 }
+
+/// Like [simplified_bounds_to_normal], but also replaces a lower bound that excludes nothing with `Unbounded`,
+/// so that all bounds matching the same normal versions are written the same way.
+/// Returns None if no normal version is in the bounds.
+pub(crate) fn canonical_bounds_to_normal<V: VersionLike>(
+    bounds: (Bound<V>, Bound<V>),
+) -> Option<(Bound<V>, Bound<V>)> {
+    let (from, to) = simplified_bounds_to_normal(bounds);
+    let minimum = Version::new(0, 0, 0);
+    if ends_before_minimum(&to, minimum.clone()) {
+        return None;
+    }
+    Some((unbound_from_minimum(from, minimum), to))
+}
+
+/// Rewrites bounds so that all bounds matching the same pre-release versions are written the same way.
+///
+/// No pre-release sorts between a normal version `x.y.z` and `x.y.(z+1)-0`,
+/// so a normal version used as a bound is moved up to the next `-0` pre-release.
+/// Returns None if no pre-release is in the bounds.
+pub(crate) fn canonical_bounds_to_pre<V: VersionLike>(
+    bounds: (Bound<V>, Bound<V>),
+) -> Option<(Bound<V>, Bound<V>)> {
+    let (mut from, mut to) = bounds;
+    if let Bound::Included(f) | Bound::Excluded(f) = &from {
+        if f.pre().is_empty() {
+            from = match bump_patch(f) {
+                Bound::Excluded(n) => Bound::Included(n),
+                _ => return None,
+            };
+        }
+    };
+    if let Bound::Included(t) | Bound::Excluded(t) = &to {
+        if t.pre().is_empty() {
+            to = bump_patch(t);
+        }
+    };
+    let minimum = Version {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        pre: Prerelease::new("0").unwrap(),
+        build: BuildMetadata::EMPTY,
+    };
+    if ends_before_minimum(&to, minimum.clone()) {
+        return None;
+    }
+    Some((unbound_from_minimum(from, minimum), to))
+}
+
+fn ends_before_minimum<V: VersionLike>(to: &Bound<V>, minimum: Version) -> bool {
+    matches!(to, Bound::Excluded(t) if t <= &V::from(minimum))
+}
+
+fn unbound_from_minimum<V: VersionLike>(from: Bound<V>, minimum: Version) -> Bound<V> {
+    match from {
+        Bound::Included(f) if f <= V::from(minimum) => Bound::Unbounded,
+        from => from,
+    }
+}
+
+/// The smallest normal version, without build metadata, that `from` allows.
+pub(crate) fn lowest_normal_from<V: VersionLike>(from: &Bound<V>) -> Option<V> {
+    match from {
+        Bound::Unbounded => Some(V::from(Version::new(0, 0, 0))),
+        Bound::Included(v) if v.pre().is_empty() => Some(v.clone()),
+        Bound::Included(v) | Bound::Excluded(v) if !v.pre().is_empty() => bump_up_to_normal(v),
+        Bound::Included(v) | Bound::Excluded(v) => match bump_patch(v) {
+            Bound::Excluded(n) => bump_up_to_normal(&n),
+            _ => None,
+        },
+    }
+}
+
+/// The smallest pre-release version, without build metadata, that `from` allows.
+pub(crate) fn lowest_pre_from<V: VersionLike>(from: &Bound<V>) -> Option<V> {
+    let bumped = match from {
+        Bound::Unbounded => {
+            return Some(V::from(Version {
+                major: 0,
+                minor: 0,
+                patch: 0,
+                pre: Prerelease::new("0").unwrap(),
+                build: BuildMetadata::EMPTY,
+            }))
+        }
+        Bound::Included(v) if !v.pre().is_empty() => return Some(v.clone()),
+        Bound::Included(v) | Bound::Excluded(v) => bump_pre(v),
+    };
+    match bumped {
+        Bound::Excluded(n) => Some(n),
+        _ => None,
+    }
+}
+
+/// The smallest pre-release with the same `major.minor.patch` as `v`.
+pub(crate) fn lowest_pre_of<V: VersionLike>(v: &V) -> V {
+    V::from(Version {
+        major: v.major(),
+        minor: v.minor(),
+        patch: v.patch(),
+        pre: Prerelease::new("0").unwrap(),
+        build: BuildMetadata::EMPTY,
+    })
+}
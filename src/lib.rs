@@ -1,7 +1,9 @@
 use std::{
     borrow::Borrow,
     cmp::{max, min},
+    collections::HashMap,
     fmt::Display,
+    iter::Peekable,
     ops::Bound,
 };
 
@@ -9,31 +11,48 @@ use pubgrub::{Range, VersionSet};
 use semver::{BuildMetadata, Comparator, Op, Prerelease, Version, VersionReq};
 
 mod bump_helpers;
+mod partial_version;
 mod semver_compatibility;
 mod small_version;
 mod version_like;
 
+pub use bump_helpers::{bump_major, bump_minor, bump_patch, prerelease_channel};
+pub use partial_version::PartialVersion;
 pub use semver_compatibility::SemverCompatibility;
 pub use small_version::SmallVersion;
 pub use version_like::VersionLike;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 use bump_helpers::{
-    between, bump_major, bump_minor, bump_patch, bump_pre, simplified_bounds_to_normal,
+    between, bump_pre, canonical_bounds_to_normal, canonical_bounds_to_pre, lowest_normal_from,
+    lowest_pre_from, lowest_pre_of, simplified_bounds_to_normal,
 };
 
-#[cfg(feature = "serde")]
-fn range_is_empty<V: PartialEq>(r: &Range<V>) -> bool {
-    r == &Range::empty()
-}
+/// The most `major.minor.patch` with pre-releases that [SemverPubgrub::to_canonical_reqs]
+/// and [SemverPubgrub::prerelease_triples] will list.
+const MAX_PRE_TRIPLES: usize = 1024;
+
+/// The `(from, to)` bounds of the segments of a range, in order,
+/// as returned by [SemverPubgrub::segments_by_bucket].
+pub type Segments<V> = Vec<(Bound<V>, Bound<V>)>;
 
 /// This needs to be bug-for-bug compatible with https://github.com/dtolnay/semver/blob/master/src/eval.rs
+///
+/// # Equality
+///
+/// `==` and `Hash` compare how a set is stored, not which versions it contains.
+/// Two sets can contain exactly the same versions and still compare unequal,
+/// for example if one of them stores bounds on pre-releases that no normal version can reach.
+/// Use [SemverPubgrub::canonical] before comparing or hashing if the set of versions is what matters,
+/// such as when using a `SemverPubgrub` as a `HashMap` key.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct SemverPubgrub<V: VersionLike> {
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "range_is_empty"))]
+    // Both fields are always written, so that formats that are not self-describing like bincode can read them back.
     #[cfg_attr(feature = "serde", serde(default = "Range::empty"))]
     normal: Range<V>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "range_is_empty"))]
     #[cfg_attr(feature = "serde", serde(default = "Range::empty"))]
     pre: Range<V>,
 }
@@ -80,10 +99,7 @@ impl<V: VersionLike> SemverPubgrub<V> {
     }
 }
 
-impl<V: VersionLike> SemverPubgrub<V>
-where
-    for<'a> SemverCompatibility: From<&'a V>,
-{
+impl<V: VersionLike> SemverPubgrub<V> {
     /// Whether cargo would require that only one package matche this range.
     ///
     /// While this crate matches the semantics of `semver`
@@ -152,6 +168,89 @@ where
 
         Some(start)
     }
+
+    /// Returns the compatibility ranges of the smallest and largest versions in `self`.
+    ///
+    /// The second is None if `self` contains versions in every compatibility range from the first on.
+    /// Every version in `self` is in a compatibility range between the two, inclusive.
+    /// Returns None if `self` is empty.
+    pub fn bounding_compatibility_range(
+        &self,
+    ) -> Option<(SemverCompatibility, Option<SemverCompatibility>)> {
+        use Bound::*;
+        let canonical = self.canonical();
+        let start = match canonical.bounding_range()?.0 {
+            Included(v) | Excluded(v) => v.into(),
+            Unbounded => SemverCompatibility::Patch(0),
+        };
+        // An `Excluded` end at the start of a compatibility range contains nothing from that range.
+        let last = |to: &Bound<V>, first: fn(&SemverCompatibility) -> Version| match to {
+            Included(v) => Some(SemverCompatibility::from(v)),
+            Excluded(v) => {
+                let compat = SemverCompatibility::from(v);
+                if v == &V::from(first(&compat)) {
+                    Some(
+                        compat
+                            .prev()
+                            .expect("canonical segments end after the smallest version"),
+                    )
+                } else {
+                    Some(compat)
+                }
+            }
+            Unbounded => None,
+        };
+        let normal_end = canonical
+            .normal
+            .bounding_range()
+            .map(|(_, to)| last(&to.cloned(), SemverCompatibility::canonical));
+        let pre_end = canonical
+            .pre
+            .bounding_range()
+            .map(|(_, to)| last(&to.cloned(), SemverCompatibility::minimum));
+        let end = match (normal_end, pre_end) {
+            (Some(None), _) | (_, Some(None)) => None,
+            (n, p) => max(n.flatten(), p.flatten()),
+        };
+        Some((start, end))
+    }
+
+    /// Returns the compatibility range that all the versions in `self` are in.
+    ///
+    /// Unlike [SemverPubgrub::only_one_compatibility_range] this looks at the versions `self` contains,
+    /// not at how it is stored.
+    /// It returns None for an empty set, where `only_one_compatibility_range` returns `Patch(0)`,
+    /// and it finds the compatibility range even if `self` stores bounds outside of it that contain no versions.
+    /// Both return None if `self` contains versions in more than one compatibility range, like `>=1`.
+    pub fn fits_single_bucket(&self) -> Option<SemverCompatibility> {
+        let canonical = self.canonical();
+        let compat = match canonical.bounding_range()?.0 {
+            Bound::Included(v) | Bound::Excluded(v) => SemverCompatibility::from(v),
+            Bound::Unbounded => SemverCompatibility::Patch(0),
+        };
+        canonical.is_within(&Self::from(&compat)).then_some(compat)
+    }
+
+    /// Returns true if `self` contains every normal version in the compatibility range `compat`.
+    ///
+    /// Pre-releases are not considered,
+    /// as a requirement only matches the pre-releases of a `major.minor.patch` it names
+    /// so none could match all the pre-releases of a compatibility range.
+    /// `^1` covers `Major(1)` even though it does not contain `1.5.0-rc`.
+    pub fn covers_bucket(&self, compat: &SemverCompatibility) -> bool {
+        let bucket = Self {
+            normal: Range::from(compat),
+            pre: Range::empty(),
+        };
+        bucket.is_within(self)
+    }
+
+    /// Returns true if every version in `self` is in `other`.
+    ///
+    /// Unlike [SemverPubgrub::subset_of] this looks at the versions contained, not at how they are stored.
+    fn is_within(&self, other: &Self) -> bool {
+        self.canonical().subset_of(&other.canonical())
+    }
 }
 
 impl<V: VersionLike> SemverPubgrub<V> {
@@ -180,6 +279,90 @@ impl<V: VersionLike> SemverPubgrub<V> {
         })
     }
 
+    /// Like [SemverPubgrub::contains_many], but yields `(is_contained, run_length)`
+    /// for each run of consecutive versions that are all contained or all not contained.
+    ///
+    /// The `versions` iterator must be sorted.
+    pub fn contains_runs<'s, I, BV>(
+        &'s self,
+        versions: I,
+    ) -> impl Iterator<Item = (bool, usize)> + 's
+    where
+        I: Iterator<Item = BV> + Clone + 's,
+        BV: Borrow<V> + 's,
+    {
+        let mut contains = self.contains_many(versions).peekable();
+        std::iter::from_fn(move || {
+            let first = contains.next()?;
+            let mut len = 1;
+            while contains.next_if_eq(&first).is_some() {
+                len += 1;
+            }
+            Some((first, len))
+        })
+    }
+
+    /// Returns the fraction of `versions` that are contained in `self`.
+    ///
+    /// The `versions` iterator must be sorted.
+    /// Returns `0.0` if `versions` is empty, as none of them are contained.
+    pub fn coverage_ratio<'s, I, BV>(&'s self, versions: I) -> f64
+    where
+        I: Iterator<Item = BV> + Clone + 's,
+        BV: Borrow<V> + 's,
+    {
+        let (matched, total) = self
+            .contains_many(versions)
+            .fold((0usize, 0usize), |(matched, total), contained| {
+                (matched + usize::from(contained), total + 1)
+            });
+        if total == 0 {
+            return 0.0;
+        }
+        matched as f64 / total as f64
+    }
+
+    /// Returns whether each version in `universe` is contained in both `self` and `other`.
+    ///
+    /// The `universe` must be sorted.
+    /// Functionally equivalent to `self.intersection(other).contains_many(universe.iter())`.
+    /// Except it does not build the intersection,
+    /// it walks `universe` once alongside the segments of both sets.
+    /// `bench-intersection` in the `crates-vers` example compares the two.
+    pub fn intersect_over_universe(&self, other: &Self, universe: &[V]) -> Vec<bool> {
+        let mut parts =
+            [&self.normal, &self.pre, &other.normal, &other.pre].map(|r| r.iter().peekable());
+        universe
+            .iter()
+            .map(|v| {
+                let (mine, theirs) = if v.pre().is_empty() { (0, 2) } else { (1, 3) };
+                in_next_segment(&mut parts[mine], v) && in_next_segment(&mut parts[theirs], v)
+            })
+            .collect()
+    }
+
+    /// Builds a set that agrees with `set` on which of the versions in `universe` it contains.
+    ///
+    /// `set` can be any [VersionSet], like a `pubgrub::Range`.
+    /// The `universe` must be sorted.
+    /// As with [SemverPubgrub::simplify], versions that are not in `universe`
+    /// may or may not be contained in the result.
+    pub fn from_version_set<S: VersionSet<V = V>>(set: &S, universe: &[V]) -> Self {
+        let singletons = |pre: bool| {
+            Range::from_iter(
+                universe
+                    .iter()
+                    .filter(|v| v.pre().is_empty() != pre && set.contains(v))
+                    .map(|v| (Bound::Included(v.clone()), Bound::Included(v.clone()))),
+            )
+        };
+        Self {
+            normal: singletons(false),
+            pre: singletons(true),
+        }
+        .simplify(universe.iter())
+    }
+
     /// Returns a simpler Range that contains the same versions
     ///
     /// For every one of the Versions provided in versions the existing range and
@@ -205,12 +388,539 @@ impl<V: VersionLike> SemverPubgrub<V> {
         }
     }
 
+    /// Returns the unique representation of the versions contained in `self`.
+    ///
+    /// `a.canonical() == b.canonical()` if and only if `a` and `b` contain the same versions.
+    /// In the canonical form the normal part only uses normal versions as bounds,
+    /// the pre-release part only starts or ends on a normal version at `-0`,
+    /// bounds that exclude nothing are `Unbounded`,
+    /// and segments that touch are merged.
+    pub fn canonical(&self) -> Self {
+        Self {
+            normal: Range::from_iter(
+                self.normal.iter().filter_map(|(from, to)| {
+                    canonical_bounds_to_normal((from.clone(), to.clone()))
+                }),
+            ),
+            pre: Range::from_iter(
+                self.pre
+                    .iter()
+                    .filter_map(|(from, to)| canonical_bounds_to_pre((from.clone(), to.clone()))),
+            ),
+        }
+    }
+
+    /// Returns true if `self` contains no versions.
+    ///
+    /// Unlike `self == &SemverPubgrub::empty()` this is true
+    /// even if `self` has segments that no version falls in.
+    pub fn is_empty(&self) -> bool {
+        self.canonical() == Self::empty()
+    }
+
+    /// Returns true if `self` contains pre-releases and no normal versions,
+    /// like `=1.2.3-alpha` or `>=1.2.3-alpha, <1.2.3`.
+    ///
+    /// Such a requirement can only be satisfied by a pre-release,
+    /// so it cannot resolve without opting in to them.
+    pub fn requires_prerelease(&self) -> bool {
+        let canonical = self.canonical();
+        canonical.normal == Range::empty() && canonical.pre != Range::empty()
+    }
+
+    /// Returns true if `self` contains no versions,
+    /// like a requirement whose comparators contradict each other such as `=1, =2`.
+    ///
+    /// This is the same as [SemverPubgrub::is_empty].
+    pub fn is_contradiction(&self) -> bool {
+        self.is_empty()
+    }
+
+    /// Returns the smallest version contained in `self`.
+    ///
+    /// Like cargo, this prefers normal releases:
+    /// if `allow_prerelease` is false only normal versions are considered.
+    /// Versions with build metadata are never returned.
+    /// Returns None if no such version is contained.
+    pub fn minimum(&self, allow_prerelease: bool) -> Option<V> {
+        let normal = self.normal.iter().find_map(|(from, _)| {
+            let candidate = lowest_normal_from(from)?;
+            self.normal.contains(&candidate).then_some(candidate)
+        });
+        if !allow_prerelease {
+            return normal;
+        }
+        let pre = self.pre.iter().find_map(|(from, _)| {
+            let candidate = lowest_pre_from(from)?;
+            self.pre.contains(&candidate).then_some(candidate)
+        });
+        match (normal, pre) {
+            (Some(n), Some(p)) => Some(min(n, p)),
+            (n, p) => n.or(p),
+        }
+    }
+
+    /// Returns the versions in `self` that are in the compatibility range `compat`.
+    pub fn retain_compatibility(&self, compat: &SemverCompatibility) -> Self {
+        self.intersection_compat(compat)
+    }
+
+    /// Equivalent to `self.intersection(&SemverPubgrub::from(compat))`.
+    pub fn intersection_compat(&self, compat: &SemverCompatibility) -> Self {
+        let r = Range::from(compat);
+        Self {
+            normal: self.normal.intersection(&r),
+            pre: self.pre.intersection(&r),
+        }
+    }
+
+    /// Equivalent to `self.union(&SemverPubgrub::from(compat))`.
+    pub fn union_compat(&self, compat: &SemverCompatibility) -> Self {
+        let r = Range::from(compat);
+        Self {
+            normal: self.normal.union(&r),
+            pre: self.pre.union(&r),
+        }
+    }
+
+    /// Returns the versions in `self` that are compatible with `v`.
+    ///
+    /// This is how cargo clips a requirement to the compatibility range of a selected version.
+    pub fn retain_compatibility_of(&self, v: &V) -> Self {
+        self.retain_compatibility(&SemverCompatibility::from(v))
+    }
+
+    /// Returns `self` grown just enough to contain `v` while staying contiguous.
+    ///
+    /// The versions matching `^v` are added,
+    /// together with the normal versions between `self` and `v`,
+    /// so growing `^1` to include `2.3.0` gives `>=1, <3`.
+    /// If `v` is a pre-release only the pre-releases of its `major.minor.patch` from `v` on are added,
+    /// the same way `^v` would match them.
+    /// If `self` already contains `v` it is returned as is.
+    pub fn grow_to_include(&self, v: &V) -> Self {
+        use Bound::*;
+        if self.contains(v) {
+            return self.clone();
+        }
+        let caret = VersionReq {
+            comparators: vec![comparator(Op::Caret, v)],
+        };
+        let lower = Self {
+            normal: Range::strictly_lower_than(v.clone()),
+            pre: Range::strictly_lower_than(v.clone()),
+        };
+        let gap = match self.intersection(&lower).bounding_range() {
+            Some((_, Included(end))) => (Excluded(end.clone()), Excluded(v.clone())),
+            Some((_, Excluded(end))) => (Included(end.clone()), Excluded(v.clone())),
+            _ => match self.bounding_range() {
+                Some((Included(start), _)) => (Included(v.clone()), Excluded(start.clone())),
+                Some((Excluded(start), _)) => (Included(v.clone()), Included(start.clone())),
+                _ => return Self::from(&caret),
+            },
+        };
+        let gap = Self {
+            normal: simplified_to_normal(&Range::from_range_bounds(gap)),
+            pre: Range::empty(),
+        };
+        self.union(&Self::from(&caret)).union(&gap)
+    }
+
+    /// Returns up to `per_segment` versions contained in `self` for each of its segments.
+    ///
+    /// For each segment it tries the lowest version, one close to the end, and one in the middle.
+    /// A segment with no upper bound ends at `18446744073709551615.18446744073709551615.18446744073709551615`.
+    /// The output is sorted, and never has build metadata.
+    pub fn sample_versions(&self, per_segment: usize) -> Vec<V> {
+        let canonical = self.canonical();
+        let mut out = Vec::new();
+        for (range, pre) in [(&canonical.normal, false), (&canonical.pre, true)] {
+            for (from, to) in range.iter() {
+                let start = if pre {
+                    lowest_pre_from(from)
+                } else {
+                    lowest_normal_from(from)
+                };
+                let end = sample_below(to, pre);
+                let middle = start.as_ref().zip(end.as_ref()).map(|(s, e)| {
+                    let mid = |a: u64, b: u64| a / 2 + b / 2 + (a % 2 + b % 2) / 2;
+                    let triple = if s.major() != e.major() {
+                        (mid(s.major(), e.major()), 0, 0)
+                    } else if s.minor() != e.minor() {
+                        (s.major(), mid(s.minor(), e.minor()), 0)
+                    } else {
+                        (s.major(), s.minor(), mid(s.patch(), e.patch()))
+                    };
+                    sample_version(triple, pre)
+                });
+                let mut samples: Vec<V> = Vec::new();
+                for candidate in [start, end, middle].into_iter().flatten() {
+                    if samples.len() < per_segment
+                        && range.contains(&candidate)
+                        && !samples.contains(&candidate)
+                    {
+                        samples.push(candidate);
+                    }
+                }
+                out.extend(samples);
+            }
+        }
+        out.sort();
+        out
+    }
+
+    /// Returns the version from `available` that cargo would pick for `self`.
+    ///
+    /// That is the highest normal version contained in `self`,
+    /// or if there are none the highest contained pre-release.
+    /// A pre-release is only contained if the requirement asked for pre-releases of its `major.minor.patch`,
+    /// so this never falls back to a pre-release the requirement did not allow.
+    /// `available` does not need to be sorted.
+    pub fn select_best<'a>(&self, available: &'a [V]) -> Option<&'a V> {
+        available
+            .iter()
+            .filter(|v| self.contains(v))
+            .max_by(|a, b| (a.pre().is_empty(), a).cmp(&(b.pre().is_empty(), b)))
+    }
+
+    /// Returns whether `self` and `other` agree on which of the `versions` they contain.
+    ///
+    /// The `versions` iterator must be sorted.
+    pub fn agrees_over<'s, I, BV>(&'s self, other: &'s Self, versions: I) -> bool
+    where
+        I: Iterator<Item = BV> + Clone + 's,
+        BV: Borrow<V> + 's,
+    {
+        self.contains_many(versions.clone())
+            .eq(other.contains_many(versions))
+    }
+
+    /// Returns a caret requirement, like `^1.2`, that contains exactly the versions in `self`.
+    ///
+    /// Of the requirements that do, this is the one written with the fewest parts.
+    /// Returns None if there is no such requirement.
+    pub fn as_caret(&self) -> Option<VersionReq> {
+        self.as_single_op(Op::Caret)
+    }
+
+    /// Returns a tilde requirement, like `~1.2`, that contains exactly the versions in `self`.
+    ///
+    /// Of the requirements that do, this is the one written with the fewest parts.
+    /// Returns None if there is no such requirement.
+    pub fn as_tilde(&self) -> Option<VersionReq> {
+        self.as_single_op(Op::Tilde)
+    }
+
+    /// Returns an exact requirement, like `=1.2`, that contains exactly the versions in `self`.
+    ///
+    /// Of the requirements that do, this is the one written with the fewest parts.
+    /// Returns None if there is no such requirement.
+    pub fn as_exact(&self) -> Option<VersionReq> {
+        self.as_single_op(Op::Exact)
+    }
+
+    fn as_single_op(&self, op: Op) -> Option<VersionReq> {
+        // Any such requirement is named after the smallest version it contains.
+        let low = self.minimum(true)?;
+        let pre = Prerelease::new(low.pre()).unwrap();
+        let candidates = if pre.is_empty() {
+            vec![
+                (None, None),
+                (Some(low.minor()), None),
+                (Some(low.minor()), Some(low.patch())),
+            ]
+        } else {
+            vec![(Some(low.minor()), Some(low.patch()))]
+        };
+        let canonical = self.canonical();
+        candidates.into_iter().find_map(|(minor, patch)| {
+            let req = VersionReq {
+                comparators: vec![Comparator {
+                    op,
+                    major: low.major(),
+                    minor,
+                    patch,
+                    pre: pre.clone(),
+                }],
+            };
+            (Self::from(&req).canonical() == canonical).then_some(req)
+        })
+    }
+
+    /// Returns a single requirement that contains every version in `self`,
+    /// widened by at most `max_extra_buckets` steps.
+    ///
+    /// With no steps this is a requirement that contains exactly the versions in `self`.
+    /// The first step fills out the compatibility ranges `self` is in, so `=1.2.3` becomes `^1`,
+    /// and each step after that adds the next compatibility range,
+    /// so with two `=1.2.3` becomes `>=1.0.0, <3.0.0`.
+    /// Widening only adds normal versions.
+    /// A pre-release can only be matched by naming it,
+    /// so if `self` starts with one the requirement starts there, like `^1.2.3-alpha`.
+    /// Any other pre-releases in `self` are not widened,
+    /// then this is a requirement that contains exactly the versions in `self`.
+    /// Returns None if there is no such requirement,
+    /// like when `self` is empty or contains versions in separate segments.
+    pub fn loosest_req_within(&self, max_extra_buckets: u64) -> Option<VersionReq> {
+        let exact = || {
+            self.as_caret()
+                .or_else(|| self.as_tilde())
+                .or_else(|| self.as_exact())
+                .or_else(|| match self.to_canonical_reqs()?.as_slice() {
+                    [req] => Some(req.clone()),
+                    _ => None,
+                })
+        };
+        let Some(extra) = max_extra_buckets.checked_sub(1) else {
+            return exact();
+        };
+        let (start, end) = self.bounding_compatibility_range()?;
+        let last = end.and_then(|end| end.advance(extra));
+        let comparators = if let Some(lowest) = self.leading_prerelease() {
+            if last == Some(start) {
+                vec![comparator(Op::Caret, &lowest)]
+            } else {
+                let to = last
+                    .and_then(|last| last.next())
+                    .map(|next| V::from(next.canonical()));
+                std::iter::once(comparator(Op::GreaterEq, &lowest))
+                    .chain(to.map(|to| comparator(Op::Less, &to)))
+                    .collect()
+            }
+        } else if last == Some(start) {
+            vec![match start {
+                SemverCompatibility::Major(major) => Comparator {
+                    op: Op::Caret,
+                    major: major.get(),
+                    minor: None,
+                    patch: None,
+                    pre: Prerelease::EMPTY,
+                },
+                SemverCompatibility::Minor(minor) => Comparator {
+                    op: Op::Caret,
+                    major: 0,
+                    minor: Some(minor.get()),
+                    patch: None,
+                    pre: Prerelease::EMPTY,
+                },
+                SemverCompatibility::Patch(patch) => Comparator {
+                    op: Op::Caret,
+                    major: 0,
+                    minor: Some(0),
+                    patch: Some(patch),
+                    pre: Prerelease::EMPTY,
+                },
+            }]
+        } else {
+            let from = V::from(start.canonical());
+            let to = last
+                .and_then(|last| last.next())
+                .map(|next| V::from(next.canonical()));
+            std::iter::once(comparator(Op::GreaterEq, &from))
+                .chain(to.map(|to| comparator(Op::Less, &to)))
+                .collect()
+        };
+        let req = VersionReq { comparators };
+        // Widening only adds normal versions, so it does not work if `self` has other pre-releases.
+        if self.is_within(&Self::from(&req)) {
+            Some(req)
+        } else {
+            exact()
+        }
+    }
+
+    /// The smallest version in `self`, if it is a pre-release.
+    fn leading_prerelease(&self) -> Option<V> {
+        let canonical = self.canonical();
+        let pre = lowest_pre_from(&canonical.pre.bounding_range()?.0.cloned())?;
+        match canonical.normal.bounding_range() {
+            Some((from, _)) if lowest_normal_from(&from.cloned()).is_some_and(|n| n < pre) => None,
+            _ => Some(pre),
+        }
+    }
+
+    /// Returns requirements that together match the versions contained in `self`.
+    ///
+    /// Ignoring build metadata, a version is contained in `self`
+    /// if and only if it matches at least one of the returned requirements.
+    /// Each segment of normal versions becomes one requirement,
+    /// written as a single caret, tilde or exact comparator if one matches it,
+    /// and so do the pre-releases of each `major.minor.patch`,
+    /// as a `VersionReq` can only match pre-releases of a `major.minor.patch` it names.
+    /// Returns None if that would take too many requirements,
+    /// like when `self` contains pre-releases of an unbounded number of `major.minor.patch`.
+    pub fn to_canonical_reqs(&self) -> Option<Vec<VersionReq>> {
+        let mut reqs: Vec<VersionReq> = self
+            .canonical()
+            .normal
+            .iter()
+            .map(|(from, to)| {
+                let segment = Self {
+                    normal: Range::from_range_bounds((from.clone(), to.clone())),
+                    pre: Range::empty(),
+                };
+                segment
+                    .as_caret()
+                    .or_else(|| segment.as_tilde())
+                    .or_else(|| segment.as_exact())
+                    .unwrap_or_else(|| VersionReq {
+                        comparators: lower_comparator(from)
+                            .into_iter()
+                            .chain(upper_comparator(to))
+                            .collect(),
+                    })
+            })
+            .collect();
+        let pieces: Vec<_> = self
+            .iter_pre_by_triple()
+            .take(MAX_PRE_TRIPLES + 1)
+            .collect();
+        if pieces.len() > MAX_PRE_TRIPLES {
+            return None;
+        }
+        for (_, piece) in pieces {
+            for (from, to) in piece.iter() {
+                // Only a comparator with a pre-release can allow pre-releases.
+                let from = match from {
+                    Bound::Unbounded => Bound::Included(V::from(Version {
+                        major: 0,
+                        minor: 0,
+                        patch: 0,
+                        pre: Prerelease::new("0").unwrap(),
+                        build: BuildMetadata::EMPTY,
+                    })),
+                    from => from.clone(),
+                };
+                reqs.push(VersionReq {
+                    comparators: lower_comparator(&from)
+                        .into_iter()
+                        .chain(upper_comparator(to))
+                        .collect(),
+                });
+            }
+        }
+        Some(reqs)
+    }
+
+    /// The `major.minor.patch` of each version that `self` contains some pre-release of, in order.
+    ///
+    /// This shows cargo's rule for pre-releases at work:
+    /// `^1.2.3-alpha` contains pre-releases of `1.2.3` only, and `^1` contains none at all.
+    /// Returns None if there are more than 1024 to list,
+    /// like for [SemverPubgrub::full] that contains pre-releases of every version.
+    pub fn prerelease_triples(&self) -> Option<Vec<(u64, u64, u64)>> {
+        let triples: Vec<_> = self
+            .iter_pre_by_triple()
+            .map(|(triple, _)| (triple.major(), triple.minor(), triple.patch()))
+            .take(MAX_PRE_TRIPLES + 1)
+            .collect();
+        (triples.len() <= MAX_PRE_TRIPLES).then_some(triples)
+    }
+
+    /// Splits the pre-release part of `self` by `major.minor.patch`.
+    ///
+    /// Yields each `major.minor.patch` as a normal version,
+    /// with the pre-releases of it that are contained in `self`.
+    fn iter_pre_by_triple(&self) -> impl Iterator<Item = (V, Range<V>)> {
+        let mut rest = self.canonical().pre;
+        std::iter::from_fn(move || {
+            let triple = match rest.bounding_range()?.0 {
+                Bound::Included(v) | Bound::Excluded(v) => {
+                    V::from(Version::new(v.major(), v.minor(), v.patch()))
+                }
+                Bound::Unbounded => V::from(Version::new(0, 0, 0)),
+            };
+            let piece = rest.intersection(&Range::between(lowest_pre_of(&triple), triple.clone()));
+            rest = match bump_patch(&triple) {
+                Bound::Excluded(next) => rest.intersection(&Range::higher_than(next)),
+                _ => Range::empty(),
+            };
+            Some((triple, piece))
+        })
+    }
+
+    /// Splits `self` by compatibility range.
+    ///
+    /// Yields each compatibility range that `self` contains versions in,
+    /// with the segments of `self` that fall inside it.
+    /// A segment that crosses into the next compatibility range is split at its [SemverCompatibility::minimum].
+    /// Like [SemverPubgrub::bounding_range] the segments cover both normal versions and pre-releases,
+    /// so they may include pre-releases that are not contained in `self`.
+    pub fn segments_by_bucket(&self) -> impl Iterator<Item = (SemverCompatibility, Segments<V>)> {
+        let canonical = self.canonical();
+        let mut rest = canonical.normal.union(&canonical.pre);
+        std::iter::from_fn(move || loop {
+            let compat = match rest.bounding_range()?.0 {
+                Bound::Included(v) | Bound::Excluded(v) => SemverCompatibility::from(v),
+                Bound::Unbounded => SemverCompatibility::Patch(0),
+            };
+            let piece = rest.intersection(&Range::from(&compat));
+            rest = match compat.next() {
+                Some(next) => rest.intersection(&Range::higher_than(V::from(next.minimum()))),
+                None => Range::empty(),
+            };
+            if piece != Range::empty() {
+                let segments = piece
+                    .iter()
+                    .map(|(from, to)| (from.clone(), to.clone()))
+                    .collect();
+                return Some((compat, segments));
+            }
+        })
+    }
+
     /// If the range was constructed using Singleton, return the version from the constructor.
     /// Otherwise, returns [None].
     pub fn as_singleton(&self) -> Option<&V> {
         self.normal.as_singleton().xor(self.pre.as_singleton())
     }
 
+    /// The range to use with a solver that only knows about normal releases.
+    ///
+    /// For a normal version `v`, `self.to_release_range().contains(&v) == self.contains(&v)`.
+    /// Whether a pre-release is in the returned range says nothing about whether it is in `self`.
+    pub fn to_release_range(&self) -> Range<V> {
+        self.normal.clone()
+    }
+
+    /// The range to use with a solver that treats pre-releases as ordinary versions.
+    ///
+    /// This contains every version in `self`, and more:
+    /// it also contains every version in the segments of either part,
+    /// like the pre-releases that fall between two normal versions `self` contains,
+    /// which cargo would not match.
+    pub fn to_combined_range(&self) -> Range<V> {
+        self.normal.union(&self.pre)
+    }
+
+    /// Each segment of the [canonical](SemverPubgrub::canonical) [combined range](SemverPubgrub::to_combined_range)
+    /// in interval notation, like `[1.2.3, 2.0.0)`.
+    ///
+    /// An unbounded side is left empty, so `<1` is `(, 1.0.0)` and `>=1` is `[1.0.0, )`.
+    /// This is meant for logs, it says nothing about cargo's rule for pre-releases.
+    pub fn to_interval_strings(&self) -> Vec<String>
+    where
+        V: Display,
+    {
+        self.canonical()
+            .to_combined_range()
+            .iter()
+            .map(|(from, to)| {
+                let from = match from {
+                    Bound::Included(v) => format!("[{v}"),
+                    Bound::Excluded(v) => format!("({v}"),
+                    Bound::Unbounded => "(".to_string(),
+                };
+                let to = match to {
+                    Bound::Included(v) => format!("{v}]"),
+                    Bound::Excluded(v) => format!("{v})"),
+                    Bound::Unbounded => ")".to_string(),
+                };
+                format!("{from}, {to}")
+            })
+            .collect()
+    }
+
     /// Iterate over the parts of the range that can match normal releases.
     pub fn iter_normal(&self) -> impl Iterator<Item = (&Bound<V>, &Bound<V>)> {
         self.normal.iter()
@@ -251,6 +961,11 @@ impl<V: VersionLike> SemverPubgrub<V> {
         }
     }
 
+    /// Returns the versions not in `self`.
+    ///
+    /// Each part is complemented on its own, so the result stores segments that contain no versions,
+    /// like the normal versions in the pre-release part.
+    /// Use [SemverPubgrub::complement_canonical] for a result that compares equal to other sets with the same versions.
     pub fn complement(&self) -> Self {
         SemverPubgrub {
             normal: self.normal.complement(),
@@ -258,6 +973,11 @@ impl<V: VersionLike> SemverPubgrub<V> {
         }
     }
 
+    /// Returns `self.complement().canonical()`.
+    pub fn complement_canonical(&self) -> Self {
+        self.complement().canonical()
+    }
+
     pub fn intersection(&self, other: &Self) -> Self {
         SemverPubgrub {
             normal: self.normal.intersection(&other.normal),
@@ -274,6 +994,29 @@ impl<V: VersionLike> SemverPubgrub<V> {
         }
     }
 
+    /// Parses `v` as a `Version` and returns whether `self` contains it.
+    ///
+    /// ```
+    /// # use semver::VersionReq;
+    /// # use semver_pubgrub::SemverPubgrub;
+    /// let req = VersionReq::parse("^1.2").unwrap();
+    /// let set = SemverPubgrub::<semver::Version>::from(&req);
+    /// assert!(set.contains_str("1.5.0").unwrap());
+    /// assert!(set.contains_str("1.5").is_err());
+    /// ```
+    pub fn contains_str(&self, v: &str) -> Result<bool, semver::Error> {
+        Ok(self.contains(&V::from(Version::parse(v)?)))
+    }
+
+    /// Returns true if `self.complement()` contains `v`, without building the complement.
+    ///
+    /// Both `contains` and `complement` treat the normal and pre-release parts separately,
+    /// `v` is looked up in exactly one of them, and `complement` negates each part on its own.
+    /// So this is always `!self.contains(v)`.
+    pub fn not_contains(&self, v: &V) -> bool {
+        !self.contains(v)
+    }
+
     pub fn union(&self, other: &Self) -> Self {
         SemverPubgrub {
             normal: self.normal.union(&other.normal),
@@ -281,7 +1024,50 @@ impl<V: VersionLike> SemverPubgrub<V> {
         }
     }
 
+    /// Returns the versions in `self` that are not in `other`, in canonical form.
+    ///
+    /// Taking the complement of `other` adds segments that contain no versions,
+    /// like the pre-releases between two normal versions,
+    /// so the result is made [SemverPubgrub::canonical] to keep it comparable with `==`.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement()).canonical()
+    }
+
+    /// Splits `self` into the versions below `v` and the versions at or above `v`.
+    ///
+    /// Both normal versions and pre-releases are split at `v`,
+    /// so each half contains exactly the versions of `self` on its side.
+    pub fn split_at(&self, v: &V) -> (Self, Self) {
+        let below = Range::strictly_lower_than(v.clone());
+        let above = Range::higher_than(v.clone());
+        (
+            Self {
+                normal: self.normal.intersection(&below),
+                pre: self.pre.intersection(&below),
+            },
+            Self {
+                normal: self.normal.intersection(&above),
+                pre: self.pre.intersection(&above),
+            },
+        )
+    }
+
+    /// Returns `self.union(other)`, and whether it contains any versions that `self` did not.
+    pub fn union_reporting(&self, other: &Self) -> (Self, bool) {
+        let union = self.union(other);
+        let grew = union.canonical() != self.canonical();
+        (union, grew)
+    }
+
     pub fn is_disjoint(&self, other: &Self) -> bool {
+        // Most pairs of requirements are far apart, which the bounding ranges show without walking the segments.
+        let (Some(bounds), Some(other_bounds)) = (self.bounding_range(), other.bounding_range())
+        else {
+            return true;
+        };
+        if ends_before(bounds.1, other_bounds.0) || ends_before(other_bounds.1, bounds.0) {
+            return true;
+        }
         self.normal.is_disjoint(&other.normal) && self.pre.is_disjoint(&other.pre)
     }
 
@@ -350,49 +1136,260 @@ impl<V: std::fmt::Debug + Display + VersionLike> VersionSet for SemverPubgrub<V>
     }
 }
 
-impl<V: VersionLike> From<&VersionReq> for SemverPubgrub<V> {
-    fn from(req: &VersionReq) -> Self {
-        let mut out = Self::full();
-        // add to normal the intersection of cmps in req
-        for cmp in &req.comparators {
-            out = out.intersection(&matches_impl(cmp));
+impl<V: VersionLike> SemverPubgrub<V> {
+    /// Like `SemverPubgrub::from(req)`, but without cargo's special treatment of pre-releases.
+    ///
+    /// Cargo only lets a pre-release match if some comparator in `req` has a pre-release
+    /// with the same `major.minor.patch`. This skips that check,
+    /// so pre-releases match wherever each comparator on its own would match them,
+    /// which is what many tools outside of cargo expect.
+    /// For example `^1` contains `1.5.0-rc` and `>=1.0.0` contains `2.0.0-alpha`,
+    /// neither of which cargo would match.
+    /// Comparators that check the pre-release directly still do so,
+    /// so `=1.2.3` does not contain `1.2.3-rc`.
+    /// The exception is a comparator with a pre-release but no patch, like `=1-alpha`,
+    /// which can only be built programmatically.
+    /// It checks the pre-release of every `major.minor.patch` it covers,
+    /// and here it is widened to contain all of their pre-releases.
+    pub fn from_version_req_prerelease_inclusive(req: &VersionReq) -> Self {
+        Self::from_comparators(req, None)
+    }
+
+    /// Like `SemverPubgrub::from(req)`, but rejects a comparator with a pre-release
+    /// and no patch, like a hand built `>=1.2-alpha`.
+    ///
+    /// `semver` will not parse these, but it matches them if they are built programmatically,
+    /// in ways that are rarely what was meant.
+    /// `From` converts them the way `semver` matches them.
+    ///
+    /// ```
+    /// # use semver::{Version, VersionReq};
+    /// # use semver_pubgrub::{SemverPubgrub, StrictError};
+    /// let mut req = VersionReq::parse(">=1.2.3-alpha").unwrap();
+    /// assert!(SemverPubgrub::<Version>::try_from_strict(&req).is_ok());
+    /// req.comparators[0].patch = None;
+    /// assert_eq!(
+    ///     SemverPubgrub::<Version>::try_from_strict(&req),
+    ///     Err(StrictError::PrereleaseWithoutPatch(req.comparators[0].clone()))
+    /// );
+    /// ```
+    pub fn try_from_strict(req: &VersionReq) -> Result<Self, StrictError> {
+        if let Some(cmp) = req
+            .comparators
+            .iter()
+            .find(|cmp| !cmp.pre.is_empty() && cmp.patch.is_none())
+        {
+            return Err(StrictError::PrereleaseWithoutPatch(cmp.clone()));
         }
-        let mut pre = Range::empty();
-        // add to pre the union of cmps in req
-        for cmp in &req.comparators {
-            pre = pre.union(&pre_is_compatible(cmp));
+        Ok(Self::from(req))
+    }
+
+    /// The intersection of `matches_impl` for each comparator in `req`.
+    ///
+    /// If `windows` is given, only the pre-releases in it need to be exact.
+    fn from_comparators(req: &VersionReq, windows: Option<&Range<V>>) -> Self {
+        let mut cmps = req.comparators.iter();
+        let Some(first) = cmps.next() else {
+            // `*` has no comparators, and matches everything.
+            return Self::full();
+        };
+        let mut out = matches_impl(first, windows);
+        // add to normal the intersection of cmps in req
+        for cmp in cmps {
+            if out == Self::empty() {
+                // No later comparator can add versions back.
+                break;
+            }
+            out = out.intersection(&matches_impl(cmp, windows));
         }
-        out.pre = pre.intersection(&out.pre);
         out
     }
 }
 
-fn matches_impl<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
-    // https://github.com/dtolnay/semver/blob/master/src/eval.rs#L30
-    match cmp.op {
-        Op::Exact | Op::Wildcard => matches_exact(cmp),
-        Op::Greater => matches_greater(cmp),
-        Op::GreaterEq => matches_exact(cmp).union(&matches_greater(cmp)),
-        Op::Less => matches_less(cmp),
-        Op::LessEq => matches_exact(cmp).union(&matches_less(cmp)),
-        Op::Tilde => matches_tilde(cmp),
-        Op::Caret => matches_caret(cmp),
-        _ => unreachable!("update to a version that supports this Op"),
+/// Why [SemverPubgrub::try_from_strict] rejected a requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrictError {
+    /// The comparator has a pre-release, but not a `major.minor.patch` for it to belong to.
+    PrereleaseWithoutPatch(Comparator),
+}
+
+impl Display for StrictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrictError::PrereleaseWithoutPatch(cmp) => write!(
+                f,
+                "comparator `{cmp}` has pre-release `{}` without a patch version",
+                cmp.pre
+            ),
+        }
     }
 }
 
-fn matches_exact<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
-    // https://github.com/dtolnay/semver/blob/master/src/eval.rs#L44
-    let low = V::from(Version {
-        major: cmp.major,
-        minor: cmp.minor.unwrap_or(0),
-        patch: cmp.patch.unwrap_or(0),
-        pre: cmp.pre.clone(),
-        build: BuildMetadata::EMPTY,
-    });
-    if !cmp.pre.is_empty() {
-        return SemverPubgrub {
-            normal: Range::empty(),
+impl std::error::Error for StrictError {}
+
+impl<V: VersionLike> SemverPubgrub<V> {
+    /// The versions matching `^major.minor.patch`, leaving out the parts that are None.
+    ///
+    /// Like in a `VersionReq` the patch can only be given with a minor, so it is ignored if `minor` is None.
+    pub fn caret_triple(major: u64, minor: Option<u64>, patch: Option<u64>) -> Self {
+        Self::from_triple(Op::Caret, major, minor, patch)
+    }
+
+    /// The versions matching `~major.minor.patch`, leaving out the parts that are None.
+    ///
+    /// Like in a `VersionReq` the patch can only be given with a minor, so it is ignored if `minor` is None.
+    pub fn tilde_triple(major: u64, minor: Option<u64>, patch: Option<u64>) -> Self {
+        Self::from_triple(Op::Tilde, major, minor, patch)
+    }
+
+    /// The versions matching `=major.minor.patch`, leaving out the parts that are None.
+    ///
+    /// Like in a `VersionReq` the patch can only be given with a minor, so it is ignored if `minor` is None.
+    pub fn exact_triple(major: u64, minor: Option<u64>, patch: Option<u64>) -> Self {
+        Self::from_triple(Op::Exact, major, minor, patch)
+    }
+
+    /// The versions matching a requirement with the single comparator `op` and `pv`.
+    ///
+    /// This is [SemverPubgrub::caret_triple] and friends for any `Op`.
+    /// A `semver::Op::Wildcard` stands for a trailing `.*`, so `1.2` with it is `1.2.*`.
+    pub fn from_partial(pv: PartialVersion, op: Op) -> Self {
+        Self::from_triple(op, pv.major, pv.minor, pv.patch)
+    }
+
+    /// Like `SemverPubgrub::from(req)`, but reuses the result if `req` was converted with the same `cache` before.
+    ///
+    /// Many requirements in the ecosystem are the same few strings like `^1` or `^0.1`,
+    /// so this can save converting them again.
+    /// Whether cloning beats converting depends on the requirements,
+    /// the `bench-conversion` mode of the `crates-vers` example compares the two.
+    pub fn from_req_cached(req: &VersionReq, cache: &mut HashMap<VersionReq, Self>) -> Self {
+        if let Some(cached) = cache.get(req) {
+            return cached.clone();
+        }
+        let out = Self::from(req);
+        cache.insert(req.clone(), out.clone());
+        out
+    }
+
+    fn from_triple(op: Op, major: u64, minor: Option<u64>, patch: Option<u64>) -> Self {
+        let req = VersionReq {
+            comparators: vec![Comparator {
+                op,
+                major,
+                minor,
+                patch: minor.and(patch),
+                pre: Prerelease::EMPTY,
+            }],
+        };
+        Self::from(&req)
+    }
+}
+
+/// Converts a requirement with the same meaning as [VersionReq::matches].
+///
+/// Normal versions are contained if every comparator matches them.
+/// Pre-releases additionally need the same `major.minor.patch` as a comparator with a pre-release,
+/// even if that comparator is only a lower bound:
+///
+/// ```
+/// # use semver::{Version, VersionReq};
+/// # use semver_pubgrub::SemverPubgrub;
+/// let req = VersionReq::parse(">=1.2.3-alpha, <2").unwrap();
+/// let set = SemverPubgrub::<Version>::from(&req);
+/// let contains = |v: &str| set.contains(&Version::parse(v).unwrap());
+/// assert!(contains("1.2.3-beta"));
+/// assert!(contains("1.2.3"));
+/// assert!(contains("1.5.0"));
+/// assert!(!contains("1.2.3-0"));
+/// assert!(!contains("1.2.4-alpha"));
+/// assert!(!contains("2.0.0-rc"));
+/// ```
+impl<V: VersionLike> From<&VersionReq> for SemverPubgrub<V> {
+    fn from(req: &VersionReq) -> Self {
+        let mut pre = Range::empty();
+        // add to pre the union of cmps in req
+        for cmp in &req.comparators {
+            pre = pre.union(&pre_is_compatible(cmp));
+        }
+        let mut out = Self::from_comparators(req, Some(&pre));
+        out.pre = pre.intersection(&out.pre);
+        out
+    }
+}
+
+/// The same as `SemverPubgrub::from(&req)`, for when the requirement is not needed afterwards.
+impl<V: VersionLike> From<VersionReq> for SemverPubgrub<V> {
+    fn from(req: VersionReq) -> Self {
+        Self::from(&req)
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl<V: VersionLike> SemverPubgrub<V> {
+    /// Panics if `self` is not what `SemverPubgrub::from(req)` could have returned.
+    ///
+    /// This checks cargo's rule for pre-releases:
+    /// every pre-release in `self` has the `major.minor.patch` of a comparator in `req` that has a pre-release.
+    /// It does not say anything about the normal part,
+    /// a requirement like `>=1.2.3-alpha, <1.2.3-beta` contains pre-releases and no normal versions.
+    /// The check walks the pre-releases one `major.minor.patch` at a time,
+    /// so it is meant for tests and fuzzing, not for every conversion.
+    pub fn check_invariants(&self, req: &VersionReq) {
+        assert!(
+            self.prereleases_are_named_by(req),
+            "{req} contains pre-releases it does not name"
+        );
+    }
+
+    fn prereleases_are_named_by(&self, req: &VersionReq) -> bool {
+        self.iter_pre_by_triple().all(|(triple, _)| {
+            req.comparators.iter().any(|cmp| {
+                !cmp.pre.is_empty()
+                    && cmp.major == triple.major()
+                    && cmp.minor == Some(triple.minor())
+                    && cmp.patch == Some(triple.patch())
+            })
+        })
+    }
+}
+
+/// The versions `cmp` matches, before cargo's rule for pre-releases.
+///
+/// `windows` are the `[T-0, T)` ranges that rule lets through,
+/// see [matches_without_patch] for why they are needed.
+fn matches_impl<V: VersionLike>(cmp: &Comparator, windows: Option<&Range<V>>) -> SemverPubgrub<V> {
+    // https://github.com/dtolnay/semver/blob/master/src/eval.rs#L30
+    match cmp.op {
+        Op::Exact | Op::Wildcard => matches_exact(cmp, windows),
+        Op::Greater => matches_greater(cmp),
+        Op::GreaterEq => matches_exact(cmp, windows).union(&matches_greater(cmp)),
+        Op::Less => matches_less(cmp),
+        Op::LessEq => matches_exact(cmp, windows).union(&matches_less(cmp)),
+        Op::Tilde => matches_tilde(cmp, windows),
+        Op::Caret => matches_caret(cmp),
+        _ => unreachable!("update to a version that supports this Op"),
+    }
+}
+
+fn matches_exact<V: VersionLike>(cmp: &Comparator, windows: Option<&Range<V>>) -> SemverPubgrub<V> {
+    // https://github.com/dtolnay/semver/blob/master/src/eval.rs#L44
+    if !cmp.pre.is_empty() && cmp.patch.is_none() {
+        return SemverPubgrub {
+            normal: Range::empty(),
+            pre: matches_without_patch(cmp, windows, |v| between(V::from(v), bump_pre)),
+        };
+    }
+    let low = V::from(Version {
+        major: cmp.major,
+        minor: cmp.minor.unwrap_or(0),
+        patch: cmp.patch.unwrap_or(0),
+        pre: cmp.pre.clone(),
+        build: BuildMetadata::EMPTY,
+    });
+    if !cmp.pre.is_empty() {
+        return SemverPubgrub {
+            normal: Range::empty(),
             pre: between(low, bump_pre),
         };
     }
@@ -457,7 +1454,7 @@ fn matches_less<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
     }
 }
 
-fn matches_tilde<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
+fn matches_tilde<V: VersionLike>(cmp: &Comparator, windows: Option<&Range<V>>) -> SemverPubgrub<V> {
     // https://github.com/dtolnay/semver/blob/master/src/eval.rs#L116
     let low = V::from(Version {
         major: cmp.major,
@@ -478,10 +1475,64 @@ fn matches_tilde<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
     } else {
         between(low, bump_major)
     };
+    // `ver.pre >= cmp.pre`, which for an empty `cmp.pre` is no pre-release at all.
+    let pre = if cmp.pre.is_empty() {
+        Range::empty()
+    } else {
+        matches_without_patch(cmp, windows, |v| {
+            let normal = Version::new(v.major, v.minor, v.patch);
+            Range::between(v, normal)
+        })
+    };
     SemverPubgrub {
         normal: simplified_to_normal(&normal),
-        pre: Range::empty(),
+        pre,
+    }
+}
+
+/// The pre-releases matched by `=` or `~` with a pre-release but no patch, like `=1-alpha`.
+///
+/// `semver` does not reject these when built programmatically,
+/// it compares the pre-release of every `major.minor.patch` the comparator covers.
+/// `matching(v)` gives the pre-releases of one of them, with `v` at `cmp.pre`.
+/// That is one segment for each `major.minor.patch`,
+/// so only the ones in `windows` are built, the others are dropped by cargo's rule anyway.
+/// Without `windows` all the pre-releases the comparator covers are returned.
+fn matches_without_patch<V: VersionLike>(
+    cmp: &Comparator,
+    windows: Option<&Range<V>>,
+    matching: impl Fn(Version) -> Range<V>,
+) -> Range<V> {
+    let low = V::from(Version {
+        major: cmp.major,
+        minor: cmp.minor.unwrap_or(0),
+        patch: 0,
+        pre: Prerelease::new("0").unwrap(),
+        build: BuildMetadata::EMPTY,
+    });
+    let span = if cmp.minor.is_some() {
+        between(low, bump_minor)
+    } else {
+        between(low, bump_major)
+    };
+    let Some(windows) = windows else {
+        return span;
+    };
+    let mut out = Range::empty();
+    for (from, _) in windows.intersection(&span).iter() {
+        let Bound::Included(from) = from else {
+            unreachable!("windows start at `-0`")
+        };
+        let v = Version {
+            major: from.major(),
+            minor: from.minor(),
+            patch: from.patch(),
+            pre: cmp.pre.clone(),
+            build: BuildMetadata::EMPTY,
+        };
+        out = out.union(&matching(v));
     }
+    out
 }
 
 fn matches_caret<V: VersionLike>(cmp: &Comparator) -> SemverPubgrub<V> {
@@ -551,6 +1602,107 @@ fn pre_is_compatible<V: VersionLike>(cmp: &Comparator) -> Range<V> {
     )
 }
 
+/// Whether no version is both below `end` and above `start`.
+fn ends_before<V: Ord>(end: Bound<&V>, start: Bound<&V>) -> bool {
+    match (end, start) {
+        (Bound::Included(e), Bound::Included(s)) => e < s,
+        (Bound::Included(e) | Bound::Excluded(e), Bound::Included(s) | Bound::Excluded(s)) => {
+            e <= s
+        }
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+    }
+}
+
+/// Whether `v` is in the first of `segments` that does not end before it.
+///
+/// Skips the segments that end before `v`, so asking about sorted versions walks the segments once.
+fn in_next_segment<'r, V: Ord + 'r>(
+    segments: &mut Peekable<impl Iterator<Item = (&'r Bound<V>, &'r Bound<V>)>>,
+    v: &V,
+) -> bool {
+    while let Some((_, to)) = segments.peek() {
+        let before_end = match to {
+            Bound::Included(t) => v <= t,
+            Bound::Excluded(t) => v < t,
+            Bound::Unbounded => true,
+        };
+        if before_end {
+            break;
+        }
+        segments.next();
+    }
+    let Some((from, _)) = segments.peek() else {
+        return false;
+    };
+    match from {
+        Bound::Included(f) => f <= v,
+        Bound::Excluded(f) => f < v,
+        Bound::Unbounded => true,
+    }
+}
+
+fn comparator<V: VersionLike>(op: Op, v: &V) -> Comparator {
+    Comparator {
+        op,
+        major: v.major(),
+        minor: Some(v.minor()),
+        patch: Some(v.patch()),
+        pre: Prerelease::new(v.pre()).unwrap(),
+    }
+}
+
+fn lower_comparator<V: VersionLike>(from: &Bound<V>) -> Option<Comparator> {
+    match from {
+        Bound::Included(v) => Some(comparator(Op::GreaterEq, v)),
+        Bound::Excluded(v) => Some(comparator(Op::Greater, v)),
+        Bound::Unbounded => None,
+    }
+}
+
+fn upper_comparator<V: VersionLike>(to: &Bound<V>) -> Option<Comparator> {
+    match to {
+        Bound::Included(v) => Some(comparator(Op::LessEq, v)),
+        Bound::Excluded(v) => Some(comparator(Op::Less, v)),
+        Bound::Unbounded => None,
+    }
+}
+
+/// A version without build metadata, that is a pre-release at `-0` if `pre`.
+fn sample_version<V: VersionLike>((major, minor, patch): (u64, u64, u64), pre: bool) -> V {
+    V::from(Version {
+        major,
+        minor,
+        patch,
+        pre: if pre {
+            Prerelease::new("0").unwrap()
+        } else {
+            Prerelease::EMPTY
+        },
+        build: BuildMetadata::EMPTY,
+    })
+}
+
+/// A version close below `to`, that is a pre-release if `pre`.
+fn sample_below<V: VersionLike>(to: &Bound<V>, pre: bool) -> Option<V> {
+    match to {
+        Bound::Included(t) => Some(t.clone()),
+        Bound::Excluded(t) if pre && t.pre() != "0" => Some(lowest_pre_of(t)),
+        Bound::Excluded(t) => {
+            let triple = if t.patch() > 0 {
+                (t.major(), t.minor(), t.patch() - 1)
+            } else if t.minor() > 0 {
+                (t.major(), t.minor() - 1, u64::MAX)
+            } else if t.major() > 0 {
+                (t.major() - 1, u64::MAX, u64::MAX)
+            } else {
+                return None;
+            };
+            Some(sample_version(triple, pre))
+        }
+        Bound::Unbounded => Some(sample_version((u64::MAX, u64::MAX, u64::MAX), pre)),
+    }
+}
+
 fn simplified_to_normal<V: VersionLike>(input: &Range<V>) -> Range<V> {
     Range::from_iter(
         input
@@ -566,6 +1718,18 @@ mod test {
 
     const OPS: &[&str] = &["^", "~", "=", "<", ">", "<=", ">="];
 
+    /// Check that `contains` agrees with `matches` for every requirement and version.
+    fn check_against_semver(raw_reqs: &[&str], raw_vers: &[&str]) {
+        for raw_req in raw_reqs {
+            let req = semver::VersionReq::parse(raw_req).unwrap();
+            let pver: SemverPubgrub<Version> = (&req).into();
+            for raw_ver in raw_vers {
+                let ver = semver::Version::parse(raw_ver).unwrap();
+                assert_eq!(req.matches(&ver), pver.contains(&ver), "{req} |=> {ver}");
+            }
+        }
+    }
+
     #[test]
     fn test_contains_overflow() {
         for op in OPS {
@@ -587,7 +1751,17 @@ mod test {
                 let req = semver::VersionReq::parse(&raw_req).unwrap();
                 let pver: SemverPubgrub<Version> = (&req).into();
                 let bounding_range = pver.bounding_range();
-                for raw_ver in ["18446744073709551615.1.0"] {
+                for raw_ver in [
+                    "18446744073709551614.18446744073709551615.0",
+                    "18446744073709551615.0.0-0",
+                    "18446744073709551615.0.0",
+                    "18446744073709551615.1.0",
+                    "18446744073709551615.5.0-rc",
+                    "18446744073709551615.5.0",
+                    "18446744073709551615.18446744073709551615.18446744073709551615-rc",
+                    "18446744073709551615.18446744073709551615.18446744073709551615",
+                    "18446744073709551615.18446744073709551615.18446744073709551615+build",
+                ] {
                     let ver = semver::Version::parse(raw_ver).unwrap();
                     let mat = req.matches(&ver);
                     if mat != pver.contains(&ver) {
@@ -603,6 +1777,18 @@ mod test {
                 }
             }
         }
+
+        // A caret at the largest major has no upper bound, but still no pre-releases.
+        let req = semver::VersionReq::parse("^18446744073709551615").unwrap();
+        let pver: SemverPubgrub<Version> = (&req).into();
+        let contains = |raw_ver: &str| pver.contains(&Version::parse(raw_ver).unwrap());
+        assert!(contains("18446744073709551615.5.0"));
+        assert!(contains(
+            "18446744073709551615.18446744073709551615.18446744073709551615"
+        ));
+        assert!(!contains("18446744073709551615.5.0-rc"));
+        assert!(!contains("18446744073709551614.18446744073709551615.0"));
+        assert_eq!(pver.iter_pre().count(), 0);
     }
 
     #[test]
@@ -647,6 +1833,1760 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_contains_mismatched_pre() {
+        check_against_semver(
+            &[
+                ">=1.2.3-alpha, <1.2.3-beta",
+                ">1.2.3-alpha, <=1.2.3-beta",
+                ">=1.2.3-beta, <1.2.3-alpha",
+                ">=1.2.3-alpha.1, <1.2.3-alpha.2",
+                ">=1.2.3-alpha, <1.2.4-beta",
+                ">=1.2.3-alpha, <1.2.3",
+                ">=1.2.3-alpha, <=1.2.3",
+                "~1.2.3-alpha, <1.2.3-rc",
+                "^1.2.3-alpha, <1.2.3-beta",
+                "=1.2.3-alpha, <1.2.3-beta",
+                ">=1.2.2-alpha, <1.2.3-beta",
+            ],
+            &[
+                "1.2.2-alpha",
+                "1.2.2",
+                "1.2.3-0",
+                "1.2.3-alpha",
+                "1.2.3-alpha.0",
+                "1.2.3-alpha.1",
+                "1.2.3-alpha.2",
+                "1.2.3-beta",
+                "1.2.3-beta.1",
+                "1.2.3-rc",
+                "1.2.3",
+                "1.2.4-alpha",
+                "1.2.4-beta",
+                "1.2.4",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_contains_less_eq() {
+        let reqs = [
+            "<=1",
+            "<=1.2",
+            "<=1.2.3",
+            "<=1.2.3-pre",
+            "<=0",
+            "<=0.0",
+            "<=0.0.0",
+        ];
+        check_against_semver(
+            &reqs,
+            &[
+                "0.0.0-0",
+                "0.0.0",
+                "0.9.0",
+                "1.0.0-0",
+                "1.0.0",
+                "1.2.2",
+                "1.2.3-0",
+                "1.2.3-pre",
+                "1.2.3-pre.0",
+                "1.2.3-rc",
+                "1.2.3",
+                "1.2.3+build",
+                "1.2.4-0",
+                "1.2.4",
+                "1.2.99",
+                "1.3.0-0",
+                "1.3.0",
+                "1.99.0",
+                "2.0.0-0",
+                "2.0.0-rc",
+                "2.0.0",
+            ],
+        );
+        for raw_req in reqs {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            for ver in testing::version_grid() {
+                assert_eq!(req.matches(&ver), pver.contains(&ver), "{req} |=> {ver}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_contains_wildcard_zero() {
+        let reqs = [
+            "0", "0.*", "0.0.*", "0.0", "0.0.0", "=0", "=0.0", "1.*", "0.1.*", "*",
+        ];
+        check_against_semver(
+            &reqs,
+            &[
+                "0.0.0-0",
+                "0.0.0-rc",
+                "0.0.0",
+                "0.0.1-0",
+                "0.0.1",
+                "0.0.99",
+                "0.1.0-rc",
+                "0.1.0",
+                "0.5.0-rc",
+                "0.5.0",
+                "0.5.0+build",
+                "1.0.0-0",
+                "1.0.0-rc",
+                "1.0.0",
+                "1.5.0-rc",
+                "2.0.0-0",
+            ],
+        );
+        for raw_req in reqs {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            for ver in testing::version_grid() {
+                assert_eq!(req.matches(&ver), pver.contains(&ver), "{req} |=> {ver}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_contains_mixed() {
+        let reqs = [
+            ">=1.2.3-alpha, <2",
+            ">=1.2.3-alpha, <1.3",
+            ">=1.2.3-alpha, <=1.2.3",
+            ">=1.2.3-alpha, <1.2.4",
+            ">1.2.3-alpha, <2.0.0-rc",
+            ">=1.2, <1.2.3-beta",
+            ">=1.2.2, <=1.2.3-beta",
+            "^1.2.3-alpha, <1.5",
+            "~1.2.3-alpha, >=1.2.3",
+            ">=1.2.3, <1.2.4-alpha",
+            ">=1.2.3-alpha, <1.2.4-alpha",
+            ">=1.2.3-alpha, ^1.2",
+        ];
+        check_against_semver(
+            &reqs,
+            &[
+                "1.2.2",
+                "1.2.2-alpha",
+                "1.2.3-0",
+                "1.2.3-alpha",
+                "1.2.3-alpha.1",
+                "1.2.3-beta",
+                "1.2.3-rc",
+                "1.2.3",
+                "1.2.3+build",
+                "1.2.4-0",
+                "1.2.4-alpha",
+                "1.2.4",
+                "1.3.0-0",
+                "1.3.0",
+                "1.4.9",
+                "1.5.0-rc",
+                "1.5.0",
+                "2.0.0-0",
+                "2.0.0-rc",
+                "2.0.0",
+            ],
+        );
+        for raw_req in reqs {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            for ver in testing::version_grid() {
+                assert_eq!(req.matches(&ver), pver.contains(&ver), "{req} |=> {ver}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_requirements() {
+        let reqs: Vec<VersionReq> = testing::SAMPLE_REQUIREMENTS
+            .lines()
+            .map(|raw_req| VersionReq::parse(raw_req).unwrap())
+            .collect();
+        assert!(reqs.len() > 300);
+
+        let pvers: Vec<SemverPubgrub<Version>> = reqs.iter().map(SemverPubgrub::from).collect();
+        // A parsed comparator matches one interval of normal versions, so their intersection is at most one segment,
+        // and pre-releases are only kept in a segment for each comparator that has a pre-release.
+        // A comparator with a pre-release but no patch can match more, but those can only be built by hand.
+        for (req, pver) in reqs.iter().zip(&pvers) {
+            let canonical = pver.canonical();
+            assert!(canonical.iter_normal().count() <= 1, "{req}");
+            let with_pre = req.comparators.iter().filter(|cmp| !cmp.pre.is_empty());
+            assert!(canonical.iter_pre().count() <= with_pre.count(), "{req}");
+        }
+
+        // Versions next to every version named in the requirements.
+        let mut versions = testing::version_grid();
+        for cmp in reqs.iter().flat_map(|req| &req.comparators) {
+            let minor = cmp.minor.unwrap_or(0);
+            let patch = cmp.patch.unwrap_or(0);
+            for (minor, patch) in [
+                (minor, patch),
+                (minor, patch.saturating_add(1)),
+                (minor.saturating_add(1), 0),
+            ] {
+                for pre in [cmp.pre.as_str(), "", "0", "zzz"] {
+                    versions.push(Version {
+                        major: cmp.major,
+                        minor,
+                        patch,
+                        pre: Prerelease::new(pre).unwrap(),
+                        build: BuildMetadata::EMPTY,
+                    });
+                }
+            }
+            versions.push(Version::new(cmp.major.saturating_add(1), 0, 0));
+        }
+        versions.sort();
+        versions.dedup();
+
+        for (req, pver) in reqs.iter().zip(&pvers) {
+            let expected = versions.iter().map(|ver| req.matches(ver));
+            assert!(pver.contains_many(versions.iter()).eq(expected), "{req}");
+        }
+    }
+
+    #[test]
+    fn test_contains_only_pre() {
+        let v = |raw_ver: &str| Version::parse(raw_ver).unwrap();
+        let req = VersionReq::parse(">=1.2.3-alpha, <1.2.3").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        assert_eq!(pver.normal, Range::empty());
+        assert_eq!(pver.pre, Range::between(v("1.2.3-alpha"), v("1.2.3")));
+
+        check_against_semver(
+            &[
+                ">=1.2.3-alpha, <1.2.3",
+                ">1.2.3-alpha, <1.2.3",
+                ">=1.2.3-alpha, <1.2.3-rc",
+                "=1.2.3-alpha",
+                "<1.2.3, >=1.2.3-0",
+                "<1.2.3, >=1.2.3-alpha, ^1.2.3-beta",
+            ],
+            &[
+                "1.2.2",
+                "1.2.3-0",
+                "1.2.3-alpha",
+                "1.2.3-alpha.0",
+                "1.2.3-beta",
+                "1.2.3-rc",
+                "1.2.3-rc.1",
+                "1.2.3",
+                "1.2.4-alpha",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_prerelease_triples() {
+        for (raw_req, triples) in [
+            ("^1", &[][..]),
+            ("^1.2.3-alpha", &[(1, 2, 3)]),
+            (">=1.2.3-alpha, <=1.2.5-beta", &[(1, 2, 3), (1, 2, 5)]),
+            (">=1.2.3-alpha, <1.2.3-alpha", &[]),
+        ] {
+            let pver = SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+            assert_eq!(
+                pver.prerelease_triples().as_deref(),
+                Some(triples),
+                "{raw_req}"
+            );
+        }
+
+        // Pre-releases of every version from some point on are too many to list.
+        let open = VersionReq::parse(">=1.2.3-alpha").unwrap();
+        for unbounded in [
+            SemverPubgrub::<Version>::full(),
+            SemverPubgrub::from_version_req_prerelease_inclusive(&open),
+            SemverPubgrub::from(&open).complement(),
+        ] {
+            assert_eq!(unbounded.prerelease_triples(), None, "{unbounded}");
+        }
+        let within = VersionReq::parse(">=1.2.3-alpha, <1.2.9").unwrap();
+        assert_eq!(
+            SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(&within)
+                .prerelease_triples()
+                .map(|triples| triples.len()),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn test_try_from_strict() {
+        for raw_req in testing::TRICKY_REQS {
+            let req = VersionReq::parse(raw_req).unwrap();
+            assert_eq!(
+                SemverPubgrub::<Version>::try_from_strict(&req),
+                Ok(SemverPubgrub::from(&req)),
+                "{raw_req}"
+            );
+        }
+        for (minor, patch) in [(Some(2), None), (None, None)] {
+            let mut req = VersionReq::parse(">=1.0.0, >=1.2.3-alpha").unwrap();
+            req.comparators[1].minor = minor;
+            req.comparators[1].patch = patch;
+            let err = SemverPubgrub::<Version>::try_from_strict(&req).unwrap_err();
+            assert_eq!(
+                err,
+                StrictError::PrereleaseWithoutPatch(req.comparators[1].clone())
+            );
+            assert!(err.to_string().contains("alpha"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_from_owned() {
+        for raw_req in ["^1.2.3", ">=1.2.3-alpha, <2"] {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let borrowed = SemverPubgrub::<Version>::from(&req);
+            let owned: SemverPubgrub<Version> = req.into();
+            assert_eq!(borrowed, owned, "{raw_req}");
+        }
+    }
+
+    #[test]
+    fn test_pre_without_patch() {
+        let vers = [
+            "0.9.0",
+            "1.0.0-alpha",
+            "1.0.0",
+            "1.2.0-alpha",
+            "1.2.3-0",
+            "1.2.3-alpha",
+            "1.2.3-beta",
+            "1.2.3",
+            "1.3.0-alpha",
+            "1.3.0",
+            "2.0.0-alpha",
+            "2.0.0",
+        ]
+        .map(|raw_ver| Version::parse(raw_ver).unwrap());
+        let ops = [
+            Op::Exact,
+            Op::Wildcard,
+            Op::Greater,
+            Op::GreaterEq,
+            Op::Less,
+            Op::LessEq,
+            Op::Tilde,
+            Op::Caret,
+        ];
+        for op in ops {
+            for minor in [None, Some(2)] {
+                // Only a comparator built by hand can have a pre-release without a patch.
+                let cmp = Comparator {
+                    op,
+                    major: 1,
+                    minor,
+                    patch: None,
+                    pre: Prerelease::new("alpha").unwrap(),
+                };
+                for other in [
+                    None,
+                    Some(">=1.2.3-alpha"),
+                    Some("<=1.2.3-beta"),
+                    Some("^1.0.0-0"),
+                    Some(">=1.3.0-0"),
+                ] {
+                    let mut req = other.map_or(VersionReq::STAR, |raw_req| {
+                        VersionReq::parse(raw_req).unwrap()
+                    });
+                    req.comparators.push(cmp.clone());
+                    let pver = SemverPubgrub::<Version>::from(&req);
+                    let inclusive = SemverPubgrub::from_version_req_prerelease_inclusive(&req);
+                    for ver in &vers {
+                        assert_eq!(req.matches(ver), pver.contains(ver), "{req} |=> {ver}");
+                        if pver.contains(ver) {
+                            assert!(inclusive.contains(ver), "{req} |=> {ver}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimum() {
+        for (raw_req, normal, pre) in [
+            ("^1.2.3-alpha", Some("1.2.3"), Some("1.2.3-alpha")),
+            (">1.2.3-alpha", Some("1.2.3"), Some("1.2.3-alpha.0")),
+            (">1.2.3", Some("1.2.4"), Some("1.2.4")),
+            (">1.2", Some("1.3.0"), Some("1.3.0")),
+            ("<1", Some("0.0.0"), Some("0.0.0")),
+            ("^0.0.0-0", Some("0.0.0"), Some("0.0.0-0")),
+            ("=1.2.3-rc", None, Some("1.2.3-rc")),
+            (">=1.2.3-alpha, <1.2.3", None, Some("1.2.3-alpha")),
+            (">=2, <1", None, None),
+        ] {
+            let req = semver::VersionReq::parse(raw_req).unwrap();
+            let pver: SemverPubgrub<Version> = (&req).into();
+            let parse = |raw_ver: Option<&str>| raw_ver.map(|v| Version::parse(v).unwrap());
+            assert_eq!(pver.minimum(false), parse(normal), "{raw_req}");
+            assert_eq!(pver.minimum(true), parse(pre), "{raw_req}");
+        }
+        assert_eq!(SemverPubgrub::<Version>::empty().minimum(true), None);
+        assert_eq!(
+            SemverPubgrub::<Version>::full().minimum(true),
+            Some(Version::parse("0.0.0-0").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_canonical_reqs_roundtrip() {
+        let versions = testing::version_grid();
+        let reqs: Vec<_> = testing::tricky_sets().map(|(_, pver)| pver).collect();
+        for pver in &reqs {
+            testing::assert_roundtrip(pver, &versions);
+            for pver2 in &reqs {
+                testing::assert_roundtrip(&pver.union(pver2), &versions);
+                testing::assert_roundtrip(&pver.intersection(pver2), &versions);
+            }
+        }
+        // Contains pre-releases of every `major.minor.patch`.
+        assert_eq!(reqs[1].complement().to_canonical_reqs(), None);
+    }
+
+    #[test]
+    fn test_canonical_reqs_idiomatic() {
+        for (raw_req, expected) in [
+            ("^1.2", "^1.2"),
+            ("^1.2.0", "^1.2"),
+            (">=1.2.0, <2.0.0", "^1.2"),
+            ("^1", "^1"),
+            ("^0.0", "^0.0"),
+            ("^0.0.3", "^0.0.3"),
+            ("~1.2.3", "~1.2.3"),
+            ("=1.2", "~1.2"),
+            ("=1", "^1"),
+            ("=1.2.3", "=1.2.3"),
+            (">=1.2.3, <1.5.0", ">=1.2.3, <1.5.0"),
+            (">=1.2.3", ">=1.2.3"),
+            ("*", "*"),
+        ] {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            let reqs = pver.to_canonical_reqs().unwrap();
+            assert_eq!(reqs.len(), 1, "{raw_req}");
+            assert_eq!(reqs[0].to_string(), expected, "{raw_req}");
+        }
+
+        let as_ops = |raw_req: &str| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            [pver.as_caret(), pver.as_tilde(), pver.as_exact()]
+                .map(|req| req.map(|req| req.to_string()))
+        };
+        assert_eq!(
+            as_ops("^1.2.3-alpha"),
+            [Some("^1.2.3-alpha".to_string()), None, None]
+        );
+        assert_eq!(
+            as_ops("=1.2.3-alpha"),
+            [None, None, Some("=1.2.3-alpha".to_string())]
+        );
+        assert_eq!(
+            as_ops("^0.1"),
+            [
+                Some("^0.1".to_string()),
+                Some("~0.1".to_string()),
+                Some("=0.1".to_string())
+            ]
+        );
+        assert_eq!(as_ops(">1.2.3, <1.5.0"), [None, None, None]);
+    }
+
+    #[test]
+    fn test_fits_single_bucket() {
+        let empty = SemverPubgrub::<Version>::empty();
+        assert_eq!(empty.fits_single_bucket(), None);
+        assert_eq!(
+            empty.only_one_compatibility_range(),
+            Some(SemverCompatibility::Patch(0))
+        );
+
+        let unbounded = SemverPubgrub::<Version>::from(&VersionReq::parse(">=1").unwrap());
+        assert_eq!(unbounded.fits_single_bucket(), None);
+        assert_eq!(unbounded.only_one_compatibility_range(), None);
+
+        let one = SemverCompatibility::from(&Version::new(1, 0, 0));
+        // The pre-release part contains no versions, but starts in another compatibility range.
+        let stray_pre = SemverPubgrub::<Version> {
+            normal: Range::between(Version::new(1, 0, 0), Version::new(2, 0, 0)),
+            pre: Range::between(Version::new(0, 9, 0), Version::parse("0.9.1-0").unwrap()),
+        };
+        assert_eq!(stray_pre.fits_single_bucket(), Some(one));
+        assert_eq!(stray_pre.only_one_compatibility_range(), None);
+
+        for (raw_req, pver) in testing::tricky_sets() {
+            if let Some(compat) = pver.fits_single_bucket() {
+                assert_eq!(
+                    pver.only_one_compatibility_range(),
+                    Some(compat),
+                    "{raw_req}"
+                );
+                assert!(pver.is_within(&SemverPubgrub::from(&compat)), "{raw_req}");
+            } else {
+                assert!(
+                    pver.is_empty() || pver.only_one_compatibility_range().is_none(),
+                    "{raw_req}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_prereleases_are_named() {
+        for raw_req in testing::TRICKY_REQS {
+            let req = VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req).check_invariants(&req);
+        }
+        let req = VersionReq::parse(">=1.2.3-alpha, <1.2.3-beta").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        assert!(pver.prereleases_are_named_by(&req));
+        assert!(pver.iter_normal().next().is_none());
+        let inclusive = SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(
+            &VersionReq::parse("^1.2.3-alpha").unwrap(),
+        );
+        assert!(!inclusive.prereleases_are_named_by(&VersionReq::parse("^1.2.3-alpha").unwrap()));
+    }
+
+    #[test]
+    fn test_prerelease_inclusive() {
+        let contains = |raw_req: &str, raw_ver: &str| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let ver = Version::parse(raw_ver).unwrap();
+            let cargo = SemverPubgrub::<Version>::from(&req).contains(&ver);
+            let inclusive = SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(&req)
+                .contains(&ver);
+            (cargo, inclusive)
+        };
+        assert_eq!(contains("^1", "1.5.0-rc"), (false, true));
+        assert_eq!(contains(">=1.0.0", "2.0.0-alpha"), (false, true));
+        assert_eq!(contains("^1", "2.0.0-rc"), (false, false));
+        assert_eq!(contains("=1.2.3", "1.2.3-rc"), (false, false));
+        assert_eq!(contains("^1.2.3-alpha", "1.2.3-beta"), (true, true));
+        assert_eq!(contains("^1.2.3-alpha", "1.2.4-beta"), (false, true));
+        assert_eq!(contains("^1", "1.5.0"), (true, true));
+
+        let universe = testing::version_grid();
+        for raw_req in testing::TRICKY_REQS {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let cargo = SemverPubgrub::<Version>::from(&req);
+            let inclusive = SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(&req);
+            assert!(cargo.subset_of(&inclusive), "{raw_req}");
+            for ver in &universe {
+                if ver.pre.is_empty() {
+                    assert_eq!(
+                        cargo.contains(ver),
+                        inclusive.contains(ver),
+                        "{raw_req} {ver}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_retain_compatibility_of() {
+        let universe = testing::version_grid();
+        let selected = Version::parse("1.2.3").unwrap();
+        let compat = SemverCompatibility::from(&selected);
+        for (raw_req, pver) in testing::tricky_sets() {
+            let clipped = pver.retain_compatibility_of(&selected);
+            assert!(clipped.subset_of(&pver), "{raw_req}");
+            for ver in &universe {
+                if ver.major == 1 {
+                    assert_eq!(clipped.contains(ver), pver.contains(ver), "{raw_req} {ver}");
+                } else {
+                    assert!(!clipped.contains(ver), "{raw_req} {ver}");
+                }
+            }
+            if clipped != SemverPubgrub::empty() {
+                assert_eq!(clipped.only_one_compatibility_range(), Some(compat));
+            }
+        }
+    }
+
+    #[test]
+    fn test_segments_by_bucket() {
+        let req = VersionReq::parse(">=1.2, <3").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        let buckets: Vec<_> = pver.segments_by_bucket().collect();
+        let compats: Vec<_> = buckets.iter().map(|(compat, _)| *compat).collect();
+        assert_eq!(
+            compats,
+            [
+                SemverCompatibility::from(&Version::new(1, 0, 0)),
+                SemverCompatibility::from(&Version::new(2, 0, 0)),
+            ]
+        );
+        let in_segments = |segments: &[(Bound<Version>, Bound<Version>)], raw_ver: &str| {
+            let ver = Version::parse(raw_ver).unwrap();
+            segments.iter().any(|s| s.contains(&ver))
+        };
+        let (_, one) = &buckets[0];
+        assert!(in_segments(one, "1.2.0"));
+        assert!(in_segments(one, "1.99.0"));
+        assert!(!in_segments(one, "1.1.0"));
+        assert!(!in_segments(one, "2.0.0"));
+        let (_, two) = &buckets[1];
+        assert!(in_segments(two, "2.0.0"));
+        assert!(in_segments(two, "2.99.0"));
+        assert!(!in_segments(two, "1.99.0"));
+        assert!(!in_segments(two, "3.0.0"));
+
+        let universe = testing::version_grid();
+        for (raw_req, pver) in testing::tricky_sets() {
+            let buckets: Vec<_> = pver.segments_by_bucket().collect();
+            assert!(buckets.windows(2).all(|w| w[0].0 < w[1].0), "{raw_req}");
+            for ver in &universe {
+                if pver.contains(ver) {
+                    let compat = SemverCompatibility::from(ver);
+                    let (_, segments) = buckets.iter().find(|(c, _)| *c == compat).unwrap();
+                    assert!(segments.iter().any(|s| s.contains(ver)), "{raw_req} {ver}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_prerelease() {
+        let long = vec!["alpha.1"; 10_000].join(".");
+        let ver = Version::parse(&format!("1.2.3-{long}")).unwrap();
+        let Bound::Excluded(bumped) = bump_pre(&ver) else {
+            panic!("a pre-release always has a next version");
+        };
+        assert_eq!(bumped.pre.as_str(), format!("{long}.0"));
+        let range = between(ver.clone(), bump_pre);
+        assert!(range.contains(&ver));
+        assert!(range.contains(&Version {
+            build: BuildMetadata::new("build").unwrap(),
+            ..ver.clone()
+        }));
+        assert!(!range.contains(&bumped));
+
+        let exact = SemverPubgrub::<Version>::from(&VersionReq::parse(&format!("={ver}")).unwrap());
+        assert!(exact.contains(&ver));
+        assert!(!exact.contains(&bumped));
+        let greater =
+            SemverPubgrub::<Version>::from(&VersionReq::parse(&format!(">{ver}")).unwrap());
+        assert!(!greater.contains(&ver));
+        assert!(greater.contains(&bumped));
+    }
+
+    #[test]
+    fn test_sample_versions() {
+        assert!(SemverPubgrub::<Version>::empty()
+            .sample_versions(3)
+            .is_empty());
+        assert!(SemverPubgrub::<Version>::full()
+            .sample_versions(0)
+            .is_empty());
+        let full = SemverPubgrub::<Version>::full().sample_versions(3);
+        assert_eq!(full.len(), 6);
+        assert!(full.contains(&Version::new(u64::MAX, u64::MAX, u64::MAX)));
+
+        let samples = |raw_req: &str, per_segment: usize| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req).sample_versions(per_segment)
+        };
+        assert_eq!(samples("^1.2.3", 1), [Version::new(1, 2, 3)]);
+        assert_eq!(
+            samples(">=1.2.3-alpha, <1.2.4", 2),
+            [
+                Version::parse("1.2.3-alpha").unwrap(),
+                Version::parse("1.2.3").unwrap(),
+            ]
+        );
+        assert!(samples(">=1", 3).contains(&Version::new(u64::MAX, u64::MAX, u64::MAX)));
+
+        for raw_req in testing::TRICKY_REQS {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            let samples = pver.sample_versions(3);
+            assert!(samples.is_sorted(), "{raw_req}");
+            let segments =
+                pver.canonical().iter_normal().count() + pver.canonical().iter_pre().count();
+            assert!(samples.len() <= 3 * segments, "{raw_req}");
+            for ver in &samples {
+                assert!(pver.contains(ver), "{raw_req} {ver}");
+                assert!(req.matches(ver), "{raw_req} {ver}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_contradiction() {
+        for raw_req in [
+            "=1, =2",
+            ">=2, <1",
+            ">1.2.3, <1.2.3",
+            "^1, ^2",
+            "~1.2, ~1.3",
+            ">=1.2.3-alpha, <1.2.3-alpha",
+            "=1.2.3-alpha, =1.2.3-beta",
+            "=1, =2, >=0",
+        ] {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            assert_eq!(pver, SemverPubgrub::empty(), "{raw_req}");
+            assert!(pver.is_contradiction(), "{raw_req}");
+        }
+        for raw_req in ["<0.0.0", "<0.0.0-0", "<0.0.0-0, >=0.0.0-0"] {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            assert!(pver.is_contradiction(), "{raw_req}");
+        }
+        for raw_req in ["*", ">=1, <2", "=1.2.3-alpha", ">1.2.3-alpha, <1.2.3"] {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            assert!(!pver.is_contradiction(), "{raw_req}");
+        }
+        let no_versions = SemverPubgrub::<Version> {
+            normal: Range::between(
+                Version::parse("1.2.3-alpha").unwrap(),
+                Version::parse("1.2.3-beta").unwrap(),
+            ),
+            pre: Range::between(Version::new(1, 2, 3), Version::parse("1.2.4-0").unwrap()),
+        };
+        assert_ne!(no_versions, SemverPubgrub::empty());
+        assert!(no_versions.is_empty());
+    }
+
+    #[test]
+    fn test_bumps() {
+        let v = Version::new(1, 2, 3);
+        let excluded = |raw_ver: &str| Bound::Excluded(Version::parse(raw_ver).unwrap());
+        assert_eq!(bump_patch(&v), excluded("1.2.4-0"));
+        assert_eq!(bump_minor(&v), excluded("1.3.0-0"));
+        assert_eq!(bump_major(&v), excluded("2.0.0-0"));
+        assert_eq!(
+            bump_patch(&Version::new(1, 2, u64::MAX)),
+            excluded("1.3.0-0")
+        );
+        assert_eq!(
+            bump_patch(&Version::new(u64::MAX, u64::MAX, u64::MAX)),
+            Bound::Unbounded
+        );
+        let small = SmallVersion::from(&v);
+        assert_eq!(
+            bump_patch(&small),
+            Bound::Excluded(SmallVersion::from(&Version::parse("1.2.4-0").unwrap()))
+        );
+
+        // The same bounds as the conversion uses.
+        let req = VersionReq::parse("~1.2.3").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        let bounds = (Bound::Included(v.clone()), bump_minor(&v));
+        for ver in testing::version_grid() {
+            if ver.pre.is_empty() {
+                assert_eq!(pver.contains(&ver), bounds.contains(&ver), "{ver}");
+            }
+        }
+        let req = VersionReq::parse("=1.2.3-alpha").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        assert!(!pver.contains(&Version::parse("1.2.4-0").unwrap()));
+        let req = VersionReq::parse("^0.0.3").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        let Bound::Excluded(next) = bump_patch(&Version::new(0, 0, 3)) else {
+            unreachable!()
+        };
+        assert!(!pver.contains(&next));
+        assert!(pver.contains(&Version::new(0, 0, 3)));
+    }
+
+    #[test]
+    fn test_compat_operations() {
+        let compats = [
+            SemverCompatibility::from(&Version::new(0, 0, 3)),
+            SemverCompatibility::from(&Version::new(0, 2, 0)),
+            SemverCompatibility::from(&Version::new(1, 0, 0)),
+            SemverCompatibility::from(&Version::new(u64::MAX, 0, 0)),
+        ];
+        for (raw_req, pver) in testing::tricky_sets() {
+            for compat in &compats {
+                let explicit = SemverPubgrub::from(compat);
+                assert_eq!(
+                    pver.intersection_compat(compat),
+                    pver.intersection(&explicit),
+                    "{raw_req} {compat:?}"
+                );
+                assert_eq!(
+                    pver.union_compat(compat),
+                    pver.union(&explicit),
+                    "{raw_req} {compat:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_ranges() {
+        let universe = testing::version_grid();
+        for (raw_req, pver) in testing::tricky_sets() {
+            let release = pver.to_release_range();
+            let combined = pver.to_combined_range();
+            for ver in &universe {
+                if ver.pre.is_empty() {
+                    assert_eq!(release.contains(ver), pver.contains(ver), "{raw_req} {ver}");
+                }
+                if pver.contains(ver) {
+                    assert!(combined.contains(ver), "{raw_req} {ver}");
+                }
+            }
+        }
+        let req = VersionReq::parse("^1").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        let rc = Version::parse("1.5.0-rc").unwrap();
+        assert!(!pver.contains(&rc));
+        assert!(pver.to_combined_range().contains(&rc));
+    }
+
+    #[test]
+    fn test_bounding_range_merge() {
+        use Bound::*;
+        let v = |raw_ver: &str| Version::parse(raw_ver).unwrap();
+        let range = |(from, to): (Bound<&str>, Bound<&str>)| {
+            Range::from_range_bounds((from.map(v), to.map(v)))
+        };
+        for (normal, pre, bounds) in [
+            // The pre-release starts first, even though the normal start is excluded.
+            (
+                (Excluded("1.0.0"), Excluded("2.0.0")),
+                (Included("1.0.0-0"), Excluded("1.0.0")),
+                (Included("1.0.0-0"), Excluded("2.0.0")),
+            ),
+            // On a tie the included bound wins, at either end.
+            (
+                (Excluded("1.0.0"), Included("2.0.0")),
+                (Included("1.0.0"), Excluded("2.0.0")),
+                (Included("1.0.0"), Included("2.0.0")),
+            ),
+            (
+                (Included("1.0.0"), Excluded("2.0.0")),
+                (Excluded("1.0.0"), Included("2.0.0")),
+                (Included("1.0.0"), Included("2.0.0")),
+            ),
+            // Otherwise the lower start and the higher end win, whichever kind they are.
+            (
+                (Included("1.0.0"), Included("1.5.0")),
+                (Excluded("0.9.0-0"), Excluded("2.0.0")),
+                (Excluded("0.9.0-0"), Excluded("2.0.0")),
+            ),
+            (
+                (Excluded("0.9.0"), Included("3.0.0")),
+                (Included("1.0.0-0"), Excluded("2.0.0")),
+                (Excluded("0.9.0"), Included("3.0.0")),
+            ),
+            (
+                (Excluded("1.0.0"), Included("1.5.0")),
+                (Excluded("1.2.0-0"), Included("1.2.0-1")),
+                (Excluded("1.0.0"), Included("1.5.0")),
+            ),
+            (
+                (Included("1.2.0"), Excluded("1.5.0")),
+                (Included("1.0.0-0"), Excluded("3.0.0-0")),
+                (Included("1.0.0-0"), Excluded("3.0.0-0")),
+            ),
+            // Unbounded wins over anything.
+            (
+                (Unbounded, Excluded("1.0.0")),
+                (Included("1.0.0-0"), Unbounded),
+                (Unbounded, Unbounded),
+            ),
+            (
+                (Included("1.0.0"), Unbounded),
+                (Unbounded, Excluded("0.5.0")),
+                (Unbounded, Unbounded),
+            ),
+        ] {
+            let set = SemverPubgrub::<Version> {
+                normal: range(normal),
+                pre: range(pre),
+            };
+            let expected = (bounds.0.map(v), bounds.1.map(v));
+            assert_eq!(
+                set.bounding_range(),
+                Some((expected.0.as_ref(), expected.1.as_ref())),
+                "{normal:?} {pre:?}"
+            );
+            // Swapping which part has which bounds does not change the result.
+            let swapped = SemverPubgrub::<Version> {
+                normal: range(pre),
+                pre: range(normal),
+            };
+            assert_eq!(swapped.bounding_range(), set.bounding_range());
+        }
+
+        // With one part empty, the bounds are those of the other part.
+        let only = range((Excluded("1.0.0-0"), Included("1.0.0")));
+        let expected = Some((Excluded(&v("1.0.0-0")), Included(&v("1.0.0"))));
+        let only_normal = SemverPubgrub::<Version> {
+            normal: only.clone(),
+            pre: Range::empty(),
+        };
+        let only_pre = SemverPubgrub::<Version> {
+            normal: Range::empty(),
+            pre: only,
+        };
+        assert_eq!(only_normal.bounding_range(), expected);
+        assert_eq!(only_pre.bounding_range(), expected);
+        assert_eq!(SemverPubgrub::<Version>::empty().bounding_range(), None);
+    }
+
+    #[test]
+    fn test_requires_prerelease() {
+        for (raw_req, requires) in [
+            ("^1", false),
+            ("^1.2.3-alpha", false),
+            ("=1.2.3-alpha", true),
+            (">=1.2.3-alpha, <1.2.3", true),
+            (">=1.2.3-alpha, <1.2.3-alpha", false),
+            ("<0.0.0", false),
+        ] {
+            let pver = SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+            assert_eq!(pver.requires_prerelease(), requires, "{raw_req}");
+        }
+        assert!(!SemverPubgrub::<Version>::empty().requires_prerelease());
+        assert!(!SemverPubgrub::<Version>::full().requires_prerelease());
+    }
+
+    #[test]
+    fn test_grow_to_include() {
+        let from =
+            |raw_req: &str| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+        let v = |raw_ver: &str| Version::parse(raw_ver).unwrap();
+
+        let pver = from("^1");
+        assert_eq!(pver.grow_to_include(&v("1.5.0")), pver);
+        let grown = pver.grow_to_include(&v("2.3.0"));
+        assert!(pver.subset_of(&grown));
+        for (raw_ver, contained) in [
+            ("1.0.0", true),
+            ("1.9.9", true),
+            ("2.0.0", true),
+            ("2.2.9", true),
+            ("2.3.0", true),
+            ("2.9.0", true),
+            ("2.3.1-rc", false),
+            ("3.0.0", false),
+        ] {
+            assert_eq!(grown.contains(&v(raw_ver)), contained, "{raw_ver}");
+        }
+        assert_eq!(grown.canonical(), from(">=1, <3").canonical());
+        assert_eq!(grown.to_canonical_reqs().unwrap().len(), 1);
+
+        let grown = from("^1.5").grow_to_include(&v("1.2.0"));
+        assert_eq!(grown.canonical(), from(">=1.2, <2").canonical());
+        assert_eq!(grown.to_canonical_reqs().unwrap().len(), 1);
+
+        let grown = pver.grow_to_include(&v("2.0.0-rc.1"));
+        assert!(pver.subset_of(&grown));
+        for (raw_ver, contained) in [
+            ("1.0.0", true),
+            ("2.0.0-alpha", false),
+            ("2.0.0-rc.1", true),
+            ("2.0.0-rc.2", true),
+            ("2.0.0", true),
+            ("2.5.0", true),
+            ("2.5.0-rc.1", false),
+        ] {
+            assert_eq!(grown.contains(&v(raw_ver)), contained, "{raw_ver}");
+        }
+
+        let grown = SemverPubgrub::empty().grow_to_include(&v("0.2.3"));
+        assert_eq!(grown, from("^0.2.3"));
+    }
+
+    #[test]
+    fn test_is_disjoint() {
+        let reqs: Vec<_> = testing::tricky_sets().map(|(_, pver)| pver).collect();
+        for a in &reqs {
+            assert!(a.is_disjoint(&SemverPubgrub::empty()));
+            for b in &reqs {
+                assert_eq!(
+                    a.is_disjoint(b),
+                    a.intersection(b) == SemverPubgrub::empty(),
+                    "{a} {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_interval_strings() {
+        let from =
+            |raw_req: &str| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+        for (raw_req, intervals) in [
+            ("^1.2.3", &["[1.2.3, 2.0.0)"][..]),
+            (">=1", &["[1.0.0, )"]),
+            ("<1", &["(, 1.0.0)"]),
+            (">1.2.3", &["[1.2.4, )"]),
+            ("=1.2.3", &["[1.2.3, 1.2.4)"]),
+            (">=1.2.3-alpha, <2", &["[1.2.3-alpha, 2.0.0)"]),
+            ("<0.0.0", &[]),
+        ] {
+            assert_eq!(from(raw_req).to_interval_strings(), intervals, "{raw_req}");
+        }
+        assert_eq!(
+            from("^1").union(&from("^3")).to_interval_strings(),
+            ["[1.0.0, 2.0.0)", "[3.0.0, 4.0.0)"]
+        );
+        assert_eq!(
+            SemverPubgrub::<Version>::full().to_interval_strings(),
+            ["(, )"]
+        );
+    }
+
+    #[test]
+    fn test_bounding_compatibility_range() {
+        let compat = |raw_ver: &str| SemverCompatibility::from(&Version::parse(raw_ver).unwrap());
+        let bounds = |raw_req: &str| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req).bounding_compatibility_range()
+        };
+        assert_eq!(
+            bounds(">=1.2, <3"),
+            Some((compat("1.0.0"), Some(compat("2.0.0"))))
+        );
+        assert_eq!(bounds("<3"), Some((compat("0.0.0"), Some(compat("2.0.0")))));
+        assert_eq!(
+            bounds("<1"),
+            Some((compat("0.0.0"), Some(compat("0.18446744073709551615.0"))))
+        );
+        assert_eq!(bounds(">=1"), Some((compat("1.0.0"), None)));
+        assert_eq!(
+            bounds("^1.2.3-alpha"),
+            Some((compat("1.0.0"), Some(compat("1.0.0"))))
+        );
+        assert_eq!(
+            bounds(">=0.2.3-alpha, <0.2.3-beta"),
+            Some((compat("0.2.0"), Some(compat("0.2.0"))))
+        );
+        assert_eq!(bounds("^1, ^2"), None);
+        assert_eq!(
+            SemverPubgrub::<Version>::empty().bounding_compatibility_range(),
+            None
+        );
+
+        let universe = testing::version_grid();
+        for (raw_req, pver) in testing::tricky_sets() {
+            let Some((start, end)) = pver.bounding_compatibility_range() else {
+                assert!(pver.is_empty(), "{raw_req}");
+                continue;
+            };
+            for ver in &universe {
+                if pver.contains(ver) {
+                    let compat = SemverCompatibility::from(ver);
+                    assert!(start <= compat, "{raw_req} {ver}");
+                    assert!(end.is_none_or(|end| compat <= end), "{raw_req} {ver}");
+                }
+            }
+            if let Some(compat) = pver.fits_single_bucket() {
+                assert_eq!((start, end), (compat, Some(compat)), "{raw_req}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_contains_str() {
+        let req = VersionReq::parse("^1.2").unwrap();
+        let pver = SemverPubgrub::<Version>::from(&req);
+        assert!(pver.contains_str("1.2.3").unwrap());
+        assert!(!pver.contains_str("1.2.3-alpha").unwrap());
+        assert!(!pver.contains_str("2.0.0").unwrap());
+        let small = SemverPubgrub::<SmallVersion>::from(&req);
+        assert!(small.contains_str("1.2.3").unwrap());
+        for raw_ver in ["1.2", "", "1.2.3.4", "v1.2.3", "1.2.3-"] {
+            assert_eq!(
+                pver.contains_str(raw_ver).unwrap_err().to_string(),
+                Version::parse(raw_ver).unwrap_err().to_string(),
+                "{raw_ver}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_loosest_req_within() {
+        let loosest = |raw_req: &str, budget: u64| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            let loosest = pver.loosest_req_within(budget)?;
+            assert!(
+                pver.is_within(&SemverPubgrub::from(&loosest)),
+                "{raw_req} {budget}"
+            );
+            Some(loosest.to_string())
+        };
+        let some = |raw_req: &str| Some(raw_req.to_string());
+        assert_eq!(loosest("=1.2.3", 0), some("=1.2.3"));
+        assert_eq!(loosest("=1.2.3", 1), some("^1"));
+        assert_eq!(loosest("=1.2.3", 2), some(">=1.0.0, <3.0.0"));
+        assert_eq!(loosest("^0.2.3", 1), some("^0.2"));
+        assert_eq!(loosest("=0.0.3", 1), some("^0.0.3"));
+        assert_eq!(loosest("^0.0.3", 3), some(">=0.0.3, <0.0.6"));
+        assert_eq!(loosest(">=1.2, <3", 0), some(">=1.2.0, <3.0.0"));
+        assert_eq!(loosest(">=1.2, <3", 1), some(">=1.0.0, <3.0.0"));
+        assert_eq!(loosest(">=1.2", 1), some(">=1.0.0"));
+        assert_eq!(loosest("^1.2.3-alpha", 0), some("^1.2.3-alpha"));
+        assert_eq!(loosest("=1.2.3-alpha", 1), some("^1.2.3-alpha"));
+        assert_eq!(loosest("^1.2.3-alpha", 5), some(">=1.2.3-alpha, <6.0.0"));
+        assert_eq!(loosest("^0.2.3-rc", 2), some(">=0.2.3-rc, <0.4.0"));
+        assert_eq!(loosest("^1, ^2", 5), None);
+        assert_eq!(
+            loosest("=18446744073709551615.0.0", u64::MAX),
+            some(">=18446744073709551615.0.0")
+        );
+
+        let split = SemverPubgrub::<Version>::from(&VersionReq::parse("=1.2.3").unwrap())
+            .union(&SemverPubgrub::from(&VersionReq::parse("=1.2.5").unwrap()));
+        assert_eq!(split.loosest_req_within(0), None);
+        assert_eq!(split.loosest_req_within(1).unwrap().to_string(), "^1");
+
+        let from =
+            |raw_req: &str| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+        let split_pre = from("=1.2.3-alpha").union(&from("=1.2.5"));
+        assert_eq!(split_pre.loosest_req_within(0), None);
+        assert_eq!(
+            split_pre.loosest_req_within(1).unwrap().to_string(),
+            "^1.2.3-alpha"
+        );
+        assert_eq!(
+            split_pre.loosest_req_within(2).unwrap().to_string(),
+            ">=1.2.3-alpha, <3.0.0"
+        );
+        // The pre-release is not first, so no requirement can name it and still reach down to `1.0.5`.
+        let pre_inside = from("=1.0.5").union(&from("=1.2.3-alpha"));
+        assert_eq!(pre_inside.loosest_req_within(1), None);
+    }
+
+    #[test]
+    fn test_covers_bucket() {
+        let one = SemverCompatibility::from(&Version::new(1, 0, 0));
+        let covers = |raw_req: &str, compat: &SemverCompatibility| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req).covers_bucket(compat)
+        };
+        assert!(covers("^1", &one));
+        assert!(covers("1.*", &one));
+        assert!(covers(">=1, <2.0.0-0", &one));
+        assert!(covers(">=1.0.0-alpha", &one));
+        assert!(covers("*", &one));
+        assert!(!covers("~1.2", &one));
+        assert!(!covers("^1.0.1", &one));
+        assert!(!covers("<1.99.0", &one));
+        assert!(!covers("^2", &one));
+
+        let zero_two = SemverCompatibility::from(&Version::new(0, 2, 0));
+        assert!(covers("^0.2", &zero_two));
+        assert!(covers("~0.2", &zero_two));
+        assert!(!covers("^0.2.1", &zero_two));
+        assert!(!covers("^0", &one));
+
+        assert!(SemverPubgrub::<Version>::full().covers_bucket(&one));
+        assert!(!SemverPubgrub::<Version>::empty().covers_bucket(&one));
+    }
+
+    #[test]
+    fn test_from_triple() {
+        let parts = [0, 1, 2, u64::MAX];
+        let mut triples = Vec::new();
+        for major in parts {
+            triples.push((major, None, None));
+            for minor in parts {
+                triples.push((major, Some(minor), None));
+                for patch in parts {
+                    triples.push((major, Some(minor), Some(patch)));
+                }
+            }
+        }
+        for (major, minor, patch) in triples {
+            let text = match (minor, patch) {
+                (None, _) => format!("{major}"),
+                (Some(minor), None) => format!("{major}.{minor}"),
+                (Some(minor), Some(patch)) => format!("{major}.{minor}.{patch}"),
+            };
+            let parsed = |op: &str| {
+                let req = VersionReq::parse(&format!("{op}{text}")).unwrap();
+                SemverPubgrub::<Version>::from(&req)
+            };
+            assert_eq!(
+                SemverPubgrub::caret_triple(major, minor, patch),
+                parsed("^"),
+                "^{text}"
+            );
+            assert_eq!(
+                SemverPubgrub::tilde_triple(major, minor, patch),
+                parsed("~"),
+                "~{text}"
+            );
+            assert_eq!(
+                SemverPubgrub::exact_triple(major, minor, patch),
+                parsed("="),
+                "={text}"
+            );
+        }
+        assert_eq!(
+            SemverPubgrub::<Version>::caret_triple(1, None, Some(5)),
+            SemverPubgrub::caret_triple(1, None, None)
+        );
+    }
+
+    #[test]
+    fn test_from_partial() {
+        let ops = [
+            (Op::Exact, "="),
+            (Op::Greater, ">"),
+            (Op::GreaterEq, ">="),
+            (Op::Less, "<"),
+            (Op::LessEq, "<="),
+            (Op::Tilde, "~"),
+            (Op::Caret, "^"),
+        ];
+        for pv in [
+            PartialVersion::major(0),
+            PartialVersion::major(1),
+            PartialVersion::minor(0, 0),
+            PartialVersion::minor(1, 2),
+            PartialVersion::patch(0, 0, 3),
+            PartialVersion::patch(1, 2, 3),
+        ] {
+            for (op, text) in ops {
+                let req = VersionReq::parse(&format!("{text}{pv}")).unwrap();
+                assert_eq!(
+                    SemverPubgrub::<Version>::from_partial(pv, op),
+                    SemverPubgrub::from(&req),
+                    "{req}"
+                );
+            }
+            if pv.patch.is_none() {
+                let req = VersionReq::parse(&format!("{pv}.*")).unwrap();
+                assert_eq!(
+                    SemverPubgrub::<Version>::from_partial(pv, Op::Wildcard),
+                    SemverPubgrub::from(&req),
+                    "{req}"
+                );
+            }
+        }
+        let ignored_patch = PartialVersion {
+            major: 1,
+            minor: None,
+            patch: Some(3),
+        };
+        assert_eq!(ignored_patch.to_string(), "1");
+        assert_eq!(
+            SemverPubgrub::<Version>::from_partial(ignored_patch, Op::Caret),
+            SemverPubgrub::from_partial(PartialVersion::major(1), Op::Caret)
+        );
+    }
+
+    #[test]
+    fn test_difference() {
+        let universe = testing::version_grid();
+        let reqs: Vec<_> = testing::tricky_sets().map(|(_, pver)| pver).collect();
+        for a in &reqs {
+            for b in &reqs {
+                let difference = a.difference(b);
+                assert_eq!(difference, difference.canonical());
+                for ver in &universe {
+                    assert_eq!(
+                        difference.contains(ver),
+                        a.contains(ver) && !b.contains(ver),
+                        "{a} - {b} |=> {ver}"
+                    );
+                }
+            }
+            assert!(a.difference(a).is_empty());
+            assert_eq!(a.difference(&SemverPubgrub::empty()), a.canonical());
+        }
+        let req =
+            |raw_req: &str| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+        assert_eq!(
+            req("^1").difference(&req("^1.2")),
+            req(">=1.0.0, <1.2.0").canonical()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_comparators() {
+        let from =
+            |raw_req: &str| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap());
+        assert_eq!(from(">=1, >=1"), from(">=1"));
+        assert_eq!(from("^1.2.3-alpha, ^1.2.3-alpha"), from("^1.2.3-alpha"));
+        assert_eq!(
+            from(">=1.2.3-alpha, <2, >=1.2.3-alpha, <2"),
+            from(">=1.2.3-alpha, <2")
+        );
+
+        let req = VersionReq::parse(">=1.2.3-alpha, <2, ^1.2").unwrap();
+        let many = VersionReq {
+            comparators: req
+                .comparators
+                .iter()
+                .cycle()
+                .take(30_000)
+                .cloned()
+                .collect(),
+        };
+        let pver = SemverPubgrub::<Version>::from(&many);
+        assert_eq!(pver, SemverPubgrub::from(&req));
+        // Repeating comparators does not pile up segments.
+        assert_eq!(pver.iter_normal().count(), 1);
+        assert_eq!(pver.iter_pre().count(), 1);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let universe = testing::version_grid();
+        let pivots = [
+            "0.0.0-0",
+            "1.2.3-alpha",
+            "1.2.3",
+            "1.2.3+build",
+            "2.0.0-0",
+            "3.4.0",
+        ]
+        .map(|raw_ver| Version::parse(raw_ver).unwrap());
+        for (raw_req, pver) in testing::tricky_sets() {
+            for pivot in &pivots {
+                let (below, above) = pver.split_at(pivot);
+                assert!(below.is_disjoint(&above), "{raw_req} {pivot}");
+                assert_eq!(below.union(&above), pver, "{raw_req} {pivot}");
+                assert_eq!(
+                    above.contains(pivot),
+                    pver.contains(pivot),
+                    "{raw_req} {pivot}"
+                );
+                assert!(!below.contains(pivot), "{raw_req} {pivot}");
+                for ver in &universe {
+                    let (half, other) = if ver < pivot {
+                        (&below, &above)
+                    } else {
+                        (&above, &below)
+                    };
+                    assert_eq!(
+                        half.contains(ver),
+                        pver.contains(ver),
+                        "{raw_req} {pivot} {ver}"
+                    );
+                    assert!(!other.contains(ver), "{raw_req} {pivot} {ver}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_no_comparators() {
+        let star = VersionReq::parse("*").unwrap();
+        assert!(star.comparators.is_empty());
+        assert_eq!(
+            SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(&star),
+            SemverPubgrub::full()
+        );
+        assert_eq!(
+            SemverPubgrub::<Version>::from(&star),
+            SemverPubgrub {
+                normal: Range::full(),
+                pre: Range::empty(),
+            }
+        );
+        let one = VersionReq::parse("^1.2.3-alpha").unwrap();
+        assert_eq!(
+            SemverPubgrub::<Version>::from_version_req_prerelease_inclusive(&one),
+            matches_impl(&one.comparators[0], None)
+        );
+    }
+
+    #[test]
+    fn test_prerelease_channel() {
+        for (raw_ver, channel) in [
+            ("1.2.3", None),
+            ("1.2.3+build", None),
+            ("1.2.3-0", None),
+            ("1.2.3-1.2", None),
+            ("1.2.3-0.alpha", None),
+            ("1.2.3-rc", Some("rc")),
+            ("1.2.3-rc.1", Some("rc")),
+            ("1.2.3-alpha.beta.1", Some("alpha")),
+            ("1.2.3-beta2", Some("beta")),
+            ("1.2.3-beta-2.x", Some("beta")),
+            ("1.2.3-RC.1+build", Some("RC")),
+        ] {
+            let ver = Version::parse(raw_ver).unwrap();
+            assert_eq!(prerelease_channel(&ver), channel, "{raw_ver}");
+            let small = SmallVersion::from(&ver);
+            assert_eq!(prerelease_channel(&small), channel, "{raw_ver}");
+        }
+    }
+
+    #[test]
+    fn test_contains_runs() {
+        let universe = testing::version_grid();
+        for (raw_req, pver) in testing::tricky_sets() {
+            let runs: Vec<_> = pver.contains_runs(universe.iter()).collect();
+            assert!(runs.iter().all(|&(_, len)| len > 0), "{raw_req}");
+            assert!(runs.windows(2).all(|w| w[0].0 != w[1].0), "{raw_req}");
+            let decoded: Vec<bool> = runs
+                .iter()
+                .flat_map(|&(contained, len)| std::iter::repeat_n(contained, len))
+                .collect();
+            let expected: Vec<bool> = pver.contains_many(universe.iter()).collect();
+            assert_eq!(decoded, expected, "{raw_req}");
+        }
+        let empty: [Version; 0] = [];
+        assert_eq!(
+            SemverPubgrub::<Version>::full()
+                .contains_runs(empty.iter())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_from_version_set() {
+        let universe = testing::version_grid();
+        let v = |raw_ver: &str| Version::parse(raw_ver).unwrap();
+        let ranges = [
+            Range::between(v("1.0.0"), v("2.0.0")),
+            Range::between(v("0.1.0-alpha"), v("0.1.0")),
+            Range::singleton(v("1.2.3-beta")).union(&Range::higher_than(v("3.0.0"))),
+            Range::full(),
+            Range::empty(),
+        ];
+        for range in &ranges {
+            let pver = SemverPubgrub::from_version_set(range, &universe);
+            assert!(
+                pver.contains_many(universe.iter())
+                    .eq(range.contains_many(universe.iter())),
+                "{range}"
+            );
+            let back = Range::from_iter(
+                universe
+                    .iter()
+                    .filter(|ver| pver.contains(ver))
+                    .map(|ver| (Bound::Included(ver.clone()), Bound::Included(ver.clone()))),
+            );
+            assert!(
+                back.contains_many(universe.iter())
+                    .eq(range.contains_many(universe.iter())),
+                "{range}"
+            );
+        }
+        for (raw_req, pver) in testing::tricky_sets() {
+            let copy = SemverPubgrub::from_version_set(&pver, &universe);
+            assert!(copy.agrees_over(&pver, universe.iter()), "{raw_req}");
+        }
+    }
+
+    #[test]
+    fn test_complement_canonical() {
+        let universe = testing::version_grid();
+        let reqs = [
+            "^1.2.3-alpha",
+            "~1.2.3-beta",
+            "=1.2.3-rc.1",
+            "<1.2.3-alpha",
+            "<=2.0.0-0",
+            ">1.2.3-alpha, <=1.2.3",
+            ">=1.2.3-alpha, <1.2.3-beta",
+            ">=1.0.0-alpha, <=1.0.0-beta, >=1.0.0-alpha.1",
+            "^1.2.3-alpha, ^1.2.4-beta",
+            ">=3.1.0-alpha.1, <3.1.1",
+            "*",
+        ]
+        .map(|raw_req| SemverPubgrub::<Version>::from(&VersionReq::parse(raw_req).unwrap()));
+        for a in &reqs {
+            let complement = a.complement_canonical();
+            assert_eq!(complement, complement.canonical());
+            for ver in &universe {
+                assert_eq!(complement.contains(ver), !a.contains(ver), "!{a} |=> {ver}");
+            }
+            assert_eq!(complement.complement_canonical(), a.canonical(), "{a}");
+            for b in &reqs {
+                // De Morgan: !(a | b) == !a & !b
+                assert_eq!(
+                    a.union(b).complement_canonical(),
+                    a.complement().intersection(&b.complement()).canonical(),
+                    "{a} {b}"
+                );
+                // De Morgan: !(a & b) == !a | !b
+                assert_eq!(
+                    a.intersection(b).complement_canonical(),
+                    a.complement_canonical()
+                        .union(&b.complement_canonical())
+                        .canonical(),
+                    "{a} {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_coverage_ratio() {
+        let universe = testing::version_grid();
+        for (raw_req, pver) in testing::tricky_sets() {
+            let matched = universe.iter().filter(|ver| pver.contains(ver)).count();
+            let expected = matched as f64 / universe.len() as f64;
+            assert_eq!(pver.coverage_ratio(universe.iter()), expected, "{raw_req}");
+        }
+        assert_eq!(
+            SemverPubgrub::<Version>::full().coverage_ratio(universe.iter()),
+            1.0
+        );
+        assert_eq!(
+            SemverPubgrub::<Version>::empty().coverage_ratio(universe.iter()),
+            0.0
+        );
+        let empty: [Version; 0] = [];
+        assert_eq!(
+            SemverPubgrub::<Version>::full().coverage_ratio(empty.iter()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_select_best() {
+        // How cargo picks a version: the highest matching normal version, or else the highest matching pre-release.
+        fn reference<'a>(req: &VersionReq, available: &'a [Version]) -> Option<&'a Version> {
+            let matching = available.iter().filter(|ver| req.matches(ver));
+            let normal = matching.clone().filter(|ver| ver.pre.is_empty()).max();
+            normal.or_else(|| matching.max())
+        }
+
+        let universe = testing::version_grid();
+        let mut shuffled = universe.clone();
+        shuffled.reverse();
+        shuffled.rotate_left(universe.len() / 3);
+        let reqs = testing::TRICKY_REQS
+            .iter()
+            .copied()
+            .chain(testing::SAMPLE_REQUIREMENTS.lines());
+        for raw_req in reqs {
+            let req = VersionReq::parse(raw_req).unwrap();
+            let pver = SemverPubgrub::<Version>::from(&req);
+            let expected = reference(&req, &universe);
+            assert_eq!(pver.select_best(&universe), expected, "{raw_req}");
+            assert_eq!(pver.select_best(&shuffled), expected, "{raw_req}");
+        }
+
+        let select = |raw_req: &str| {
+            let req = VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req)
+                .select_best(&universe)
+                .map(|ver| ver.to_string())
+        };
+        assert_eq!(select("^1.2").as_deref(), Some("1.3.4"));
+        assert_eq!(select("=1.2.3-rc.1").as_deref(), Some("1.2.3-rc.1"));
+        assert_eq!(
+            select(">=1.2.3-alpha, <1.2.3").as_deref(),
+            Some("1.2.3-rc.1")
+        );
+        assert_eq!(select(">=1.2.3-alpha, <=1.2.3").as_deref(), Some("1.2.3"));
+        assert_eq!(select("^4"), None);
+        assert_eq!(SemverPubgrub::<Version>::full().select_best(&[]), None);
+    }
+
+    #[test]
+    fn test_from_req_cached() {
+        let reqs: Vec<VersionReq> = testing::SAMPLE_REQUIREMENTS
+            .lines()
+            .chain(testing::TRICKY_REQS)
+            .chain(testing::TRICKY_REQS)
+            .map(|raw_req| VersionReq::parse(raw_req).unwrap())
+            .collect();
+        let mut cache = HashMap::new();
+        for req in &reqs {
+            let cached = SemverPubgrub::<Version>::from_req_cached(req, &mut cache);
+            assert_eq!(cached, SemverPubgrub::from(req), "{req}");
+        }
+        let unique: HashSet<&VersionReq> = reqs.iter().collect();
+        assert_eq!(cache.len(), unique.len());
+        assert!(cache.len() < reqs.len());
+    }
+
+    #[test]
+    fn test_union_reporting() {
+        let parse = |raw_req: &str| {
+            let req = semver::VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req)
+        };
+        for (raw_req, raw_req2, grew) in [
+            ("^1", "^1.2", false),
+            ("^1", "=1.2.3", false),
+            ("^1", "^1", false),
+            ("^1", "^2", true),
+            ("^1", "^1.2.3-alpha", true),
+            ("^1.2", "^1", true),
+            (">=1, <1", "^1", true),
+            ("^1", ">=1, <1", false),
+        ] {
+            let pver = parse(raw_req);
+            let (union, reported) = pver.union_reporting(&parse(raw_req2));
+            assert_eq!(union, pver.union(&parse(raw_req2)));
+            assert_eq!(reported, grew, "{raw_req} {raw_req2}");
+        }
+        // Adding pre-release bounds that no pre-release can match is not growth.
+        let v = |raw_ver: &str| semver::Version::parse(raw_ver).unwrap();
+        let pver = parse("^1");
+        let unreachable = SemverPubgrub {
+            normal: Range::empty(),
+            pre: Range::singleton(v("1.5.0")),
+        };
+        assert!(!pver.union_reporting(&unreachable).1);
+    }
+
+    #[test]
+    fn test_compatibility_from_version_like() {
+        for raw_ver in [
+            "0.0.0-0",
+            "0.0.0",
+            "0.0.1",
+            "0.0.7-r",
+            "0.1.0",
+            "0.3.7",
+            "1.0.0-0",
+            "1.0.0",
+            "2.3.4",
+            "2.3.4-r.1+build",
+            "0.0.18446744073709551615",
+            "0.18446744073709551615.0",
+            "18446744073709551615.0.0",
+        ] {
+            let ver = semver::Version::parse(raw_ver).unwrap();
+            let small: SmallVersion = (&ver).into();
+            let compat = SemverCompatibility::from(&ver);
+            assert_eq!(compat, SemverCompatibility::from(&small), "{raw_ver}");
+            let expected = if ver.major != 0 {
+                SemverCompatibility::Major(ver.major.try_into().unwrap())
+            } else if ver.minor != 0 {
+                SemverCompatibility::Minor(ver.minor.try_into().unwrap())
+            } else {
+                SemverCompatibility::Patch(ver.patch)
+            };
+            assert_eq!(compat, expected, "{raw_ver}");
+        }
+    }
+
+    #[test]
+    fn test_intersect_over_universe() {
+        let raw_vers = [
+            "0.0.0-0", "0.0.0", "0.1.0", "0.9.8-r", "0.9.8", "1.0.0-0", "1.0.0", "1.0.1-z0",
+            "1.0.1", "1.2.0", "2.0.0-r", "2.0.0", "3.0.0",
+        ];
+        let vers = raw_vers.map(|raw_ver| semver::Version::parse(raw_ver).unwrap());
+        assert!(vers.is_sorted());
+        let reqs = [
+            "*",
+            "^0.9.8-r",
+            ">=0.9",
+            "<2",
+            "^1",
+            "~1.0.1-z0",
+            ">1.0.0, <=2.0.0-r",
+            "=3",
+        ]
+        .map(|raw_req| {
+            let req = semver::VersionReq::parse(raw_req).unwrap();
+            SemverPubgrub::<Version>::from(&req)
+        });
+        for pver in &reqs {
+            for pver2 in &reqs {
+                let inter = pver.intersection(pver2);
+                let expected: Vec<bool> = vers.iter().map(|v| inter.contains(v)).collect();
+                assert_eq!(pver.intersect_over_universe(pver2, &vers), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let v = |raw_ver: &str| semver::Version::parse(raw_ver).unwrap();
+        let between = |low: &str, high: &str| Range::<Version>::between(v(low), v(high));
+        let same = [
+            (
+                SemverPubgrub {
+                    normal: between("1.0.0-0", "2.0.0-0"),
+                    pre: Range::empty(),
+                },
+                SemverPubgrub {
+                    normal: between("1.0.0", "2.0.0"),
+                    pre: Range::empty(),
+                },
+            ),
+            (
+                SemverPubgrub {
+                    normal: Range::higher_than(v("0.0.0-0")),
+                    pre: Range::higher_than(v("0.0.0-0")),
+                },
+                SemverPubgrub::full(),
+            ),
+            (
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: Range::from_range_bounds(v("1.0.0-a")..=v("1.0.0")),
+                },
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: between("1.0.0-a", "1.0.1-0"),
+                },
+            ),
+            (
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: Range::strictly_higher_than(v("1.0.0")),
+                },
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: Range::higher_than(v("1.0.1-0")),
+                },
+            ),
+            (
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: between("1.0.0-0", "1.0.0").union(&between("1.0.1-0", "1.0.1")),
+                },
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: between("1.0.0-0", "1.0.2-0"),
+                },
+            ),
+            (
+                SemverPubgrub {
+                    normal: Range::empty(),
+                    pre: Range::singleton(v("1.0.0")),
+                },
+                SemverPubgrub::empty(),
+            ),
+        ];
+        for (a, b) in same {
+            assert_ne!(a, b);
+            assert_eq!(a.canonical(), b.canonical(), "{a:?} {b:?}");
+            assert_eq!(a.canonical(), a.canonical().canonical());
+        }
+
+        let raw_vers = [
+            "0.0.0-0", "0.0.0", "0.9.8-r", "0.9.8", "1.0.0-0", "1.0.0-a", "1.0.0", "1.0.1-z0",
+            "1.0.1", "1.0.2-0", "2.0.0",
+        ];
+        for op in OPS {
+            for psot in ["0.9.8-r", "1.0.0-a", "1.0", "1.0.1"] {
+                let req = semver::VersionReq::parse(&format!("{op}{psot}")).unwrap();
+                let pver: SemverPubgrub<Version> = (&req).into();
+                for pver in [pver.clone(), pver.complement()] {
+                    let canonical = pver.canonical();
+                    assert_eq!(canonical, canonical.canonical());
+                    for raw_ver in raw_vers {
+                        let ver = v(raw_ver);
+                        assert_eq!(pver.contains(&ver), canonical.contains(&ver));
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_only_one_compatibility_range() {
         let raw_vers = [
@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+/// A version with only some of `major.minor.patch` given, like the `1.2` in `^1.2`.
+///
+/// Unlike a [Comparator](semver::Comparator) this has no pre-release.
+/// Like in a `VersionReq` the patch can only be given with a minor, so it is ignored if `minor` is None.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct PartialVersion {
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+impl PartialVersion {
+    /// Just a major, like `1`.
+    pub fn major(major: u64) -> Self {
+        Self {
+            major,
+            minor: None,
+            patch: None,
+        }
+    }
+
+    /// A major and minor, like `1.2`.
+    pub fn minor(major: u64, minor: u64) -> Self {
+        Self {
+            major,
+            minor: Some(minor),
+            patch: None,
+        }
+    }
+
+    /// All of `major.minor.patch`, like `1.2.3`.
+    pub fn patch(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor: Some(minor),
+            patch: Some(patch),
+        }
+    }
+}
+
+/// Writes the parts that are given, the way they would appear in a `VersionReq`.
+impl Display for PartialVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+            if let Some(patch) = self.patch {
+                write!(f, ".{patch}")?;
+            }
+        }
+        Ok(())
+    }
+}
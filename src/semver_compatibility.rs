@@ -90,6 +90,39 @@ impl SemverCompatibility {
         }
     }
 
+    /// The compatibility range just before this one.
+    pub(crate) fn prev(&self) -> Option<SemverCompatibility> {
+        match *self {
+            Self::Patch(s) => s.checked_sub(1).map(Self::Patch),
+            Self::Minor(s) => Some(
+                NonZeroU64::new(s.get() - 1)
+                    .map(Self::Minor)
+                    .unwrap_or(Self::Patch(u64::MAX)),
+            ),
+            Self::Major(s) => Some(
+                NonZeroU64::new(s.get() - 1)
+                    .map(Self::Major)
+                    .unwrap_or(Self::Minor(NonZeroU64::MAX)),
+            ),
+        }
+    }
+
+    /// The compatibility range `n` after this one, or None if there are not that many.
+    pub(crate) fn advance(&self, n: u64) -> Option<SemverCompatibility> {
+        let one = NonZeroU64::new(1).unwrap();
+        match *self {
+            Self::Patch(s) => match s.checked_add(n) {
+                Some(new) => Some(Self::Patch(new)),
+                None => Self::Minor(one).advance(n - (u64::MAX - s) - 1),
+            },
+            Self::Minor(s) => match s.checked_add(n) {
+                Some(new) => Some(Self::Minor(new)),
+                None => Self::Major(one).advance(n - (u64::MAX - s.get()) - 1),
+            },
+            Self::Major(s) => s.checked_add(n).map(Self::Major),
+        }
+    }
+
     pub fn maximum_bound(&self) -> Bound<Version> {
         if let Some(next) = self.next() {
             Bound::Excluded(next.minimum())
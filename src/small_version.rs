@@ -234,6 +234,19 @@ impl<'a> From<&'a SmallVersion> for RefIner<'a> {
 }
 
 impl SmallVersion {
+    /// Returns `(major, minor, patch, is_pre)` if the version is stored inline,
+    /// without building a `semver::Version`.
+    ///
+    /// Returns None if the version is stored on the heap.
+    /// A version stored inline has no build metadata,
+    /// and if it is a pre-release then the pre-release is `"0"`.
+    pub fn packed_parts(&self) -> Option<(u64, u64, u64, bool)> {
+        match RefIner::from(self) {
+            RefIner::Full(_) => None,
+            RefIner::Packed(s) => Some((s.major(), s.minor(), s.patch(), !s.pre_is_empty())),
+        }
+    }
+
     pub fn into_version(&self) -> semver::Version {
         match RefIner::from(self) {
             RefIner::Full(v) => v.clone(),
@@ -248,6 +261,95 @@ impl SmallVersion {
     }
 }
 
+#[test]
+fn packed_parts() {
+    for (raw_ver, parts) in [
+        ("0.0.0", Some((0, 0, 0, false))),
+        ("1.2.3", Some((1, 2, 3, false))),
+        ("1.2.3-0", Some((1, 2, 3, true))),
+        ("255.255.255", Some((255, 255, 255, false))),
+        ("1.2.3-alpha", None),
+        ("1.2.3-0.1", None),
+        ("1.2.3+build", None),
+        ("70000.0.0", None),
+        ("0.0.18446744073709551615", None),
+    ] {
+        let v: SmallVersion = semver::Version::parse(raw_ver).unwrap().into();
+        assert_eq!(v.packed_parts(), parts, "{raw_ver}");
+        assert_eq!(v.is_small(), parts.is_some());
+    }
+}
+
+#[test]
+fn cmp_full_against_packed_pre() {
+    let raw_vers = [
+        "1.2.2",
+        "1.2.3-0",
+        "1.2.3-0+b",
+        "1.2.3-0.0",
+        "1.2.3-0.1",
+        "1.2.3-1",
+        "1.2.3-alpha",
+        "1.2.3",
+        "1.2.3+b",
+        "1.2.4-0",
+    ];
+    let vers = raw_vers.map(|raw_ver| semver::Version::parse(raw_ver).unwrap());
+    assert!(vers.is_sorted());
+    for v1 in &vers {
+        for v2 in &vers {
+            let s1: SmallVersion = v1.into();
+            let s2: SmallVersion = v2.into();
+            assert_eq!(s1.cmp(&s2), v1.cmp(v2), "{v1} cmp {v2}");
+            assert_eq!(s1 == s2, v1 == v2, "{v1} == {v2}");
+        }
+    }
+}
+
+#[cfg(test)]
+fn packed_versions(max: u64) -> Vec<semver::Version> {
+    let mut versions = Vec::new();
+    for major in 0..=max {
+        for minor in 0..=max {
+            for patch in 0..=max {
+                for pre in ["0", ""] {
+                    versions.push(semver::Version {
+                        major,
+                        minor,
+                        patch,
+                        pre: semver::Prerelease::new(pre).unwrap(),
+                        build: semver::BuildMetadata::EMPTY,
+                    });
+                }
+            }
+        }
+    }
+    versions
+}
+
+#[test]
+fn cmp_packed_pairwise() {
+    let vers = packed_versions(5);
+    let smalls: Vec<SmallVersion> = vers.iter().map(SmallVersion::from).collect();
+    assert!(smalls.iter().all(|s| s.packed_parts().is_some()));
+    for (v1, s1) in vers.iter().zip(&smalls) {
+        for (v2, s2) in vers.iter().zip(&smalls) {
+            assert_eq!(s1.cmp(s2), v1.cmp(v2), "{v1} cmp {v2}");
+        }
+    }
+}
+
+#[test]
+fn cmp_packed_sorted() {
+    let mut vers = packed_versions(20);
+    let mut smalls: Vec<SmallVersion> = vers.iter().map(SmallVersion::from).collect();
+    assert!(smalls.iter().all(|s| s.packed_parts().is_some()));
+    vers.sort();
+    smalls.sort();
+    let smalls: Vec<semver::Version> = smalls.iter().map(|s| s.into_version()).collect();
+    assert_eq!(smalls, vers);
+}
+
 impl std::hash::Hash for SmallVersion {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         RefIner::from(self).hash(state)
@@ -389,6 +491,10 @@ impl Ord for SmallVersion {
                 }
                 s.build.cmp(&o.build)
             }
+            // With the same `major.minor.patch`, a `Full` version is never equal to a `Packed` one.
+            // Either it has build metadata, so it sorts after the same version without it,
+            // or it has a pre-release other than `"0"`, which is the smallest pre-release.
+            // So the `Full` is only smaller if it is a pre-release and the `Packed` is not.
             (RefIner::Full(s), RefIner::Packed(o)) => {
                 if o.pre_is_empty() && !s.pre.is_empty() {
                     return core::cmp::Ordering::Less;
@@ -0,0 +1,103 @@
+//! Helpers for checking a `SemverPubgrub` against the `VersionReq` it came from.
+//!
+//! These are used by the tests of this crate,
+//! and are available to other crates with the `testing` feature.
+
+use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+
+use crate::SemverPubgrub;
+
+/// Requirements that exercise the corners of the conversion from `VersionReq`:
+/// pre-release bounds, wildcards, and several comparators in one requirement.
+pub const TRICKY_REQS: [&str; 36] = [
+    "*",
+    "^1.2.3",
+    "^0.2.3",
+    "^0.0.3",
+    "^0",
+    "^0.0",
+    "~1.2.3",
+    "~1.2",
+    "~1",
+    "=1.2.3",
+    "=1.2",
+    "1.*",
+    "1.2.*",
+    "0.*",
+    ">1.2.3",
+    ">=1.2.3",
+    "<1.2.3",
+    "<=1.2.3",
+    ">1.2",
+    "<=1",
+    "^1.2.3-alpha",
+    "~1.2.3-beta",
+    "=1.2.3-rc.1",
+    "^0.0.0-0",
+    "<1.2.3-alpha",
+    "<=2.0.0-0",
+    ">1.2.3-alpha, <=1.2.3",
+    ">=1.2.3-alpha, <1.2.3-beta",
+    ">=1.2.3-alpha, <2",
+    ">=1.2.3-alpha, <=1.2.4-beta",
+    ">=1.0.0-alpha, <=1.0.0-beta, >=1.0.0-alpha.1",
+    "^1.2.3-alpha, ^1.2.4-beta",
+    ">=0.1, <0.2.1-rc.1",
+    ">=1, <3, ~2",
+    "^1, ^2",
+    ">=3.1.0-alpha.1, <3.1.1",
+];
+
+/// Each of [TRICKY_REQS] together with what it converts to.
+pub fn tricky_sets() -> impl Iterator<Item = (&'static str, SemverPubgrub<Version>)> {
+    TRICKY_REQS.into_iter().map(|raw_req| {
+        let req = VersionReq::parse(raw_req).unwrap();
+        (raw_req, SemverPubgrub::from(&req))
+    })
+}
+
+/// A few hundred requirements from the crates.io index, one per line.
+///
+/// Sampled from `examples/crates-vers/data/requirements.csv`,
+/// with extra weight on requirements with pre-releases or several comparators.
+pub const SAMPLE_REQUIREMENTS: &str = include_str!("testing/requirements.txt");
+
+/// Every combination of a few small `major.minor.patch` with a few pre-releases, sorted.
+pub fn version_grid() -> Vec<Version> {
+    let mut versions = Vec::new();
+    for major in 0..=3 {
+        for minor in 0..=3 {
+            for patch in 0..=4 {
+                for pre in ["", "0", "alpha", "alpha.1", "beta", "rc.1"] {
+                    versions.push(Version {
+                        major,
+                        minor,
+                        patch,
+                        pre: Prerelease::new(pre).unwrap(),
+                        build: BuildMetadata::EMPTY,
+                    });
+                }
+            }
+        }
+    }
+    versions.sort();
+    versions
+}
+
+/// Panics if reparsing the output of [SemverPubgrub::to_canonical_reqs]
+/// changes which of the `versions` are contained.
+///
+/// The `versions` must be sorted.
+pub fn assert_roundtrip(pver: &SemverPubgrub<Version>, versions: &[Version]) {
+    let reqs = pver
+        .to_canonical_reqs()
+        .expect("should only use pre-releases of a few `major.minor.patch`");
+    let roundtrip = reqs.iter().fold(SemverPubgrub::empty(), |acc, req| {
+        let req: VersionReq = req.to_string().parse().unwrap();
+        acc.union(&(&req).into())
+    });
+    if !pver.agrees_over(&roundtrip, versions.iter()) {
+        let reqs: Vec<String> = reqs.iter().map(|req| req.to_string()).collect();
+        panic!("{pver:?} roundtripped through {}", reqs.join(" || "));
+    }
+}